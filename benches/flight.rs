@@ -0,0 +1,144 @@
+// cargo bench --bench flight
+// steps a batting practice worth of balls through the internal integrator, no rendering or avian
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use avian3d::prelude::*;
+use bevy::{math::DVec3, prelude::*, time::TimeUpdateStrategy};
+use bevy_avian_baseball_flight::prelude::*;
+
+// counts allocations, so stepping can be checked for per frame allocations
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const BALLS: usize = 200;
+const FRAMES: usize = 60;
+
+fn app(ssw_on: bool, balls: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1. / 60.,
+    )));
+    app.add_plugins(BaseballFlightPlugin {
+        ssw_on,
+        integration_mode: FlightIntegrationMode::Internal,
+        ..BaseballFlightPlugin::new(Update)
+    });
+
+    let entities = (0..balls)
+        .map(|i| {
+            let spin = Vec3::new(-2200., 0., 300. + i as f32) * RPM_TO_RADS;
+            app.world_mut()
+                .spawn((
+                    BaseballFlightState::default(),
+                    Transform::from_xyz(0., 1.8, 18.),
+                    LinearVelocity(Vec3::new(0., 0., -40.)),
+                    AngularVelocity(swap_coordinates_vec3(&spin)),
+                ))
+                .id()
+        })
+        .collect::<Vec<_>>();
+    for entity in entities {
+        app.world_mut()
+            .send_event(ActivateAerodynamicsEvent::from_angles(entity, 0., 0.));
+    }
+    // first update only sets up time
+    app.update();
+    app
+}
+
+fn bench(name: &str, ssw_on: bool, balls: usize) {
+    let mut app = app(ssw_on, balls);
+    // the first frame with balls flying sets up the schedule's buffers
+    app.update();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        app.update();
+    }
+    let elapsed = start.elapsed();
+    // includes bevy's own allocations, the flight's are the difference to the run without balls
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "{name}: {balls} balls x {FRAMES} frames in {elapsed:?}, {:?} and {} allocations per frame",
+        elapsed / FRAMES as u32,
+        allocations / FRAMES
+    );
+}
+
+fn bench_headless(n: usize) {
+    let config = BaseballPluginConfig::default();
+    let specs = (0..n)
+        .map(|i| PitchSpec {
+            spin: PitchSpec::default().spin + DVec3::Z * i as f64 * 0.01,
+            ..default()
+        })
+        .collect::<Vec<_>>();
+    let start = Instant::now();
+    let trajectories = simulate_many(&config, &specs);
+    println!(
+        "simulate_many: {} pitches in {:?}",
+        trajectories.len(),
+        start.elapsed()
+    );
+}
+
+// the adaptive integrator has to land on the fixed step rk4 trajectory
+fn compare_integrators() {
+    let rk4 = BaseballPluginConfig::default();
+    let dormand_prince = BaseballPluginConfig {
+        integrator: Integrator::dormand_prince(),
+        ..default()
+    };
+    let spec = PitchSpec::default();
+
+    let start = Instant::now();
+    let fixed = simulate(&rk4, &spec).expect("default pitch is valid");
+    let fixed_elapsed = start.elapsed();
+    let start = Instant::now();
+    let adaptive = simulate(&dormand_prince, &spec).expect("default pitch is valid");
+    let adaptive_elapsed = start.elapsed();
+
+    let deviation = fixed
+        .samples
+        .iter()
+        .zip(&adaptive.samples)
+        .map(|(a, b)| a.translation.distance(b.translation))
+        .fold(0., f64::max);
+    println!(
+        "rk4 {fixed_elapsed:?}, dormand-prince {adaptive_elapsed:?}, max deviation {:.4} in",
+        deviation * 12.
+    );
+}
+
+fn main() {
+    bench("empty", true, 0);
+    bench("ssw", true, BALLS);
+    bench("no ssw", false, BALLS);
+    bench_headless(10_000);
+    compare_integrators();
+}
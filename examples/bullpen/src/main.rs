@@ -33,17 +33,26 @@ fn main() {
     app.add_plugins(BlenvyPlugin::default());
     app.add_plugins(PhysicsPlugins::default());
     app.add_plugins(NoCameraPlayerPlugin);
+    app.add_plugins(BaseballFlightDebugPlugin);
+    app.add_plugins(PlateOverlayPlugin);
     app.add_plugins(BaseballFlightPlugin {
         ssw_on: true,
         magnus_on: true,
         drag_on: true,
+        ..default()
     });
+    // a 6'2" batter
+    app.insert_resource(StrikeZone::from_batter_height(1.88));
 
     app.add_systems(PostStartup, (setup_scene, spawn_camera.after(setup_scene)));
 
     app.add_systems(
         Update,
-        spawn_ball.run_if(input_just_released(KeyCode::KeyR)),
+        (
+            spawn_ball.run_if(input_just_released(KeyCode::KeyR)),
+            toggle_crossing_color.run_if(input_just_released(KeyCode::KeyC)),
+            log_plate_crossings,
+        ),
     );
 
     app.run();
@@ -80,39 +89,33 @@ fn spawn_ball(
     mut ev_activate_aerodynamics: EventWriter<ActivateAerodynamicsEvent>,
 ) {
     let gyro_pole = GyroPole::default();
-    let spin_efficiency: f32 = 0.0;
+    let spin_efficiency = 0.0;
     let spin_rate: f32 = 2400.;
     let velocity: f32 = 96. * MPH_TO_FTS;
-    let spin_rate: f32 = 2400.;
     let seam_z_angle: f32 = PI / 2.;
-    let tilt = Tilt::from_hour_mintes(12, 0);
-
-    let fixed_spin_rate = if spin_rate == 0. { 1. } else { spin_rate };
-
-    let gyro = match gyro_pole {
-        GyroPole::Left => spin_efficiency.asin(),
-        GyroPole::Right => PI - spin_efficiency.asin(),
+    let handedness = Handedness::Right;
+    let pitch_type = PitchType::FourSeam;
+
+    let spin_axis = match SpinAxis::from_clock(12, 0, spin_efficiency, gyro_pole) {
+        Ok(spin_axis) => spin_axis.for_handedness(handedness),
+        Err(err) => {
+            warn!("not throwing: {err}");
+            return;
+        }
     };
-
-    let spin_x_0 = fixed_spin_rate * (spin_efficiency * tilt.get().sin());
-    let spin_y_0 = fixed_spin_rate * gyro.cos(); // ((1. - spin_efficiency.powi(2)).sqrt());
-    let spin_z_0 = -fixed_spin_rate * (spin_efficiency * tilt.get().cos());
-    let spin = Vec3::new(
-        spin_x_0 * RPM_TO_RADS,
-        spin_y_0 * RPM_TO_RADS, // - RPM_TO_RAD ???
-        spin_z_0 * RPM_TO_RADS,
-    );
+    let spin = spin_axis.spin((spin_rate * RPM_TO_RADS) as f64).as_vec3();
 
     let entity = commands
         .spawn((
             Name::new("ball"),
             //
             BaseballFlightBundle::default(),
+            pitch_type,
             //
             ExternalForce::new(Vec3::ZERO),
             Transform::from_translation(Vec3::new(0.48, 1.82, 16.764)),
             LinearVelocity((-Vec3::Y * velocity).from_baseball_coord_to_bevy()),
-            AngularVelocity(spin.from_baseball_coord_to_bevy()),
+            AngularVelocity(swap_coordinates_vec3(&spin)),
             //
             Restitution {
                 coefficient: 0.546,
@@ -121,9 +124,38 @@ fn spawn_ball(
         ))
         .id();
 
-    ev_activate_aerodynamics.send(ActivateAerodynamicsEvent {
+    ev_activate_aerodynamics.send(ActivateAerodynamicsEvent::from_angles(
         entity,
-        seam_y_angle: 0.,
+        0.,
         seam_z_angle,
-    });
+    ));
+}
+
+fn toggle_crossing_color(mut overlay: ResMut<PlateOverlay>) {
+    overlay.color = match overlay.color {
+        CrossingColor::PitchType => CrossingColor::Outcome,
+        CrossingColor::Outcome => CrossingColor::PitchType,
+    };
+}
+
+fn log_plate_crossings(
+    mut ev_plate_crossing_event: EventReader<PlateCrossingEvent>,
+    query_pitch_type: Query<&PitchType>,
+) {
+    for event in ev_plate_crossing_event.read() {
+        // inches, as pitch design tools report them
+        let location = event.location / 0.0254;
+        info!(
+            "{:?} crossed at {:.1} in, {:.1} in high, {:.1} mph, {}",
+            query_pitch_type.get(event.entity).ok(),
+            location.x,
+            location.y,
+            event.velocity.length() / 0.44704,
+            if event.in_strike_zone {
+                "strike"
+            } else {
+                "ball"
+            },
+        );
+    }
 }
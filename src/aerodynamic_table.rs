@@ -0,0 +1,407 @@
+use crate::*;
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    utils::HashMap,
+};
+use std::fmt;
+
+/// registers the `.aero.csv` table asset. tables are assigned to balls through `AerodynamicTableHandle`
+pub struct AerodynamicTablePlugin;
+
+impl Plugin for AerodynamicTablePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AerodynamicTable>();
+        app.init_asset_loader::<AerodynamicTableLoader>();
+
+        app.add_systems(Update, apply_aerodynamic_tables);
+    }
+}
+
+/// measured Cl/Cd over spin factor, Reynolds number and seam angle, interpolated trilinearly.
+///
+/// loaded from csv with the header `spin_factor,reynolds,seam_angle,cl,cd` (any column order)
+/// and one row per grid point. every axis has to be strictly increasing in the order its
+/// values first appear, and every combination of axis values has to be present exactly once.
+/// outside of the table the nearest edge value is used
+#[derive(Debug, Clone, Asset, TypePath)]
+pub struct AerodynamicTable {
+    spin_factors: Vec<f64>,
+    reynolds: Vec<f64>,
+    seam_angles: Vec<f64>,
+    // indexed [spin_factor][reynolds][seam_angle]
+    cl: Vec<f64>,
+    cd: Vec<f64>,
+}
+
+#[derive(Debug)]
+pub enum AerodynamicTableError {
+    Io(std::io::Error),
+    Empty,
+    MissingColumn(&'static str),
+    // line number is 1 based
+    Malformed { line: usize, reason: String },
+    NonMonotonic { axis: &'static str, line: usize },
+    DuplicatePoint { line: usize },
+    MissingPoints { expected: usize, found: usize },
+}
+
+impl fmt::Display for AerodynamicTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read aerodynamic table: {err}"),
+            Self::Empty => write!(f, "aerodynamic table has no rows"),
+            Self::MissingColumn(column) => {
+                write!(f, "aerodynamic table is missing the `{column}` column")
+            }
+            Self::Malformed { line, reason } => {
+                write!(f, "aerodynamic table line {line} is malformed: {reason}")
+            }
+            Self::NonMonotonic { axis, line } => write!(
+                f,
+                "aerodynamic table `{axis}` axis is not strictly increasing at line {line}"
+            ),
+            Self::DuplicatePoint { line } => {
+                write!(f, "aerodynamic table line {line} repeats a grid point")
+            }
+            Self::MissingPoints { expected, found } => write!(
+                f,
+                "aerodynamic table grid needs {expected} points but only {found} were given"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AerodynamicTableError {}
+
+impl From<std::io::Error> for AerodynamicTableError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+const COLUMNS: [&str; 5] = ["spin_factor", "reynolds", "seam_angle", "cl", "cd"];
+
+impl AerodynamicTable {
+    pub fn from_csv(csv: &str) -> Result<Self, AerodynamicTableError> {
+        let mut lines = csv
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (_, header) = lines.next().ok_or(AerodynamicTableError::Empty)?;
+        let header = header.split(',').map(str::trim).collect::<Vec<_>>();
+        let mut column_index = [0; 5];
+        for (index, column) in column_index.iter_mut().zip(COLUMNS) {
+            *index = header
+                .iter()
+                .position(|&name| name == column)
+                .ok_or(AerodynamicTableError::MissingColumn(column))?;
+        }
+
+        let mut rows = Vec::new();
+        for (line, row) in lines {
+            let fields = row.split(',').map(str::trim).collect::<Vec<_>>();
+            if fields.len() != header.len() {
+                return Err(AerodynamicTableError::Malformed {
+                    line,
+                    reason: format!("expected {} fields, got {}", header.len(), fields.len()),
+                });
+            }
+            let mut values = [0.; 5];
+            for (value, (&index, column)) in values.iter_mut().zip(column_index.iter().zip(COLUMNS))
+            {
+                *value = fields[index]
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| AerodynamicTableError::Malformed {
+                        line,
+                        reason: format!("`{}` is not a number for `{column}`", fields[index]),
+                    })?;
+            }
+            rows.push((line, values));
+        }
+        if rows.is_empty() {
+            return Err(AerodynamicTableError::Empty);
+        }
+
+        let spin_factors = Self::axis(&rows, 0, COLUMNS[0])?;
+        let reynolds = Self::axis(&rows, 1, COLUMNS[1])?;
+        let seam_angles = Self::axis(&rows, 2, COLUMNS[2])?;
+
+        let expected = spin_factors.len() * reynolds.len() * seam_angles.len();
+        let mut cl = vec![f64::NAN; expected];
+        let mut cd = vec![f64::NAN; expected];
+        for (line, values) in &rows {
+            // axis values come straight from the rows, so the lookups can't miss
+            let i = spin_factors.iter().position(|&x| x == values[0]).unwrap();
+            let j = reynolds.iter().position(|&x| x == values[1]).unwrap();
+            let k = seam_angles.iter().position(|&x| x == values[2]).unwrap();
+            let index = (i * reynolds.len() + j) * seam_angles.len() + k;
+            if !cl[index].is_nan() {
+                return Err(AerodynamicTableError::DuplicatePoint { line: *line });
+            }
+            cl[index] = values[3];
+            cd[index] = values[4];
+        }
+        if rows.len() != expected {
+            return Err(AerodynamicTableError::MissingPoints {
+                expected,
+                found: rows.len(),
+            });
+        }
+
+        Ok(Self {
+            spin_factors,
+            reynolds,
+            seam_angles,
+            cl,
+            cd,
+        })
+    }
+
+    // distinct values of a column in order of first appearance, which must be increasing
+    fn axis(
+        rows: &[(usize, [f64; 5])],
+        column: usize,
+        axis: &'static str,
+    ) -> Result<Vec<f64>, AerodynamicTableError> {
+        let mut values: Vec<f64> = Vec::new();
+        for (line, row) in rows {
+            let value = row[column];
+            if values.contains(&value) {
+                continue;
+            }
+            if values.last().is_some_and(|&last| value < last) {
+                return Err(AerodynamicTableError::NonMonotonic { axis, line: *line });
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    // lower index and weight of the upper neighbour, clamped to the ends of the axis
+    fn bracket(axis: &[f64], x: f64) -> (usize, usize, f64) {
+        if axis.len() == 1 || x.is_nan() || x <= axis[0] {
+            return (0, 0, 0.);
+        }
+        let last = axis.len() - 1;
+        if x >= axis[last] {
+            return (last, last, 0.);
+        }
+        let upper = axis.partition_point(|&value| value <= x);
+        let lower = upper - 1;
+        (
+            lower,
+            upper,
+            (x - axis[lower]) / (axis[upper] - axis[lower]),
+        )
+    }
+
+    fn interpolate(&self, values: &[f64], inputs: &AerodynamicInputs) -> f64 {
+        let (i_0, i_1, t_i) = Self::bracket(&self.spin_factors, inputs.spin_factor);
+        let (j_0, j_1, t_j) = Self::bracket(&self.reynolds, inputs.reynolds);
+        let (k_0, k_1, t_k) = Self::bracket(&self.seam_angles, inputs.seam_angle);
+
+        let at = |i: usize, j: usize, k: usize| {
+            values[(i * self.reynolds.len() + j) * self.seam_angles.len() + k]
+        };
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+        let along_k = |i: usize, j: usize| lerp(at(i, j, k_0), at(i, j, k_1), t_k);
+        let along_j = |i: usize| lerp(along_k(i, j_0), along_k(i, j_1), t_j);
+        lerp(along_j(i_0), along_j(i_1), t_i)
+    }
+}
+
+impl AerodynamicModel for AerodynamicTable {
+    fn lift_coefficient(&self, inputs: &AerodynamicInputs) -> f64 {
+        self.interpolate(&self.cl, inputs)
+    }
+
+    fn drag_coefficient(&self, inputs: &AerodynamicInputs) -> f64 {
+        self.interpolate(&self.cd, inputs)
+    }
+}
+
+#[derive(Default)]
+pub struct AerodynamicTableLoader;
+
+impl AssetLoader for AerodynamicTableLoader {
+    type Asset = AerodynamicTable;
+    type Settings = ();
+    type Error = AerodynamicTableError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut csv = String::new();
+        reader.read_to_string(&mut csv).await?;
+        AerodynamicTable::from_csv(&csv)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aero.csv"]
+    }
+}
+
+/// flies the ball with a table model once the asset has loaded, and again whenever it's reloaded
+#[derive(Debug, Clone, Component)]
+pub struct AerodynamicTableHandle(pub Handle<AerodynamicTable>);
+
+fn apply_aerodynamic_tables(
+    mut commands: Commands,
+    tables: Res<Assets<AerodynamicTable>>,
+    mut ev_asset: EventReader<AssetEvent<AerodynamicTable>>,
+    // one model per table, shared by every ball flying it
+    mut models: Local<HashMap<AssetId<AerodynamicTable>, AerodynamicModelHandle>>,
+    query: Query<(
+        Entity,
+        &AerodynamicTableHandle,
+        Option<&AerodynamicModelHandle>,
+    )>,
+) {
+    let mut modified = Vec::new();
+    for ev in ev_asset.read() {
+        match ev {
+            AssetEvent::Modified { id } => {
+                models.remove(id);
+                modified.push(*id);
+            }
+            AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
+                models.remove(id);
+            }
+            _ => {}
+        }
+    }
+
+    for (entity, table_handle, model) in &query {
+        let id = table_handle.0.id();
+        if model.is_some() && !modified.contains(&id) {
+            continue;
+        }
+        if let Some(table) = tables.get(id) {
+            let model = models
+                .entry(id)
+                .or_insert_with(|| AerodynamicModelHandle::new(table.clone()));
+            commands.entity(entity).insert(model.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // cl = 0.1 + s + re / 1e6 + a / 10 and cd = 0.3 + s / 2 - re / 1e7 + a / 100, both
+    // multilinear so trilinear interpolation is exact
+    const GRID: &str = "spin_factor,reynolds,seam_angle,cl,cd
+        0,100000,0,0.2,0.29
+        0,100000,1,0.3,0.30
+        0,200000,0,0.3,0.28
+        0,200000,1,0.4,0.29
+        0.2,100000,0,0.4,0.39
+        0.2,100000,1,0.5,0.40
+        0.2,200000,0,0.5,0.38
+        0.2,200000,1,0.6,0.39";
+
+    fn inputs(spin_factor: f64, reynolds: f64, seam_angle: f64) -> AerodynamicInputs {
+        AerodynamicInputs {
+            speed: 130.,
+            spin_rate: 230.,
+            spin_factor,
+            reynolds,
+            seam_angle,
+            time_elapsed: 0.,
+        }
+    }
+
+    #[test]
+    fn interpolates_between_grid_points() {
+        let table = AerodynamicTable::from_csv(GRID).unwrap();
+        let inputs = inputs(0.05, 150000., 0.25);
+        assert!((table.lift_coefficient(&inputs) - 0.325).abs() < 1e-12);
+        assert!((table.drag_coefficient(&inputs) - 0.3125).abs() < 1e-12);
+    }
+
+    #[test]
+    fn holds_the_edge_outside_the_grid() {
+        let table = AerodynamicTable::from_csv(GRID).unwrap();
+        let inputs = inputs(1., 50000., -1.);
+        assert!((table.lift_coefficient(&inputs) - 0.4).abs() < 1e-12);
+        assert!((table.drag_coefficient(&inputs) - 0.39).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rejects_a_table_without_rows() {
+        assert!(matches!(
+            AerodynamicTable::from_csv(""),
+            Err(AerodynamicTableError::Empty)
+        ));
+        assert!(matches!(
+            AerodynamicTable::from_csv("spin_factor,reynolds,seam_angle,cl,cd"),
+            Err(AerodynamicTableError::Empty)
+        ));
+    }
+
+    #[test]
+    fn rejects_a_bad_header() {
+        assert!(matches!(
+            AerodynamicTable::from_csv("spin_factor,reynolds,cl,cd\n0,100000,0.2,0.29"),
+            Err(AerodynamicTableError::MissingColumn("seam_angle"))
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_rows() {
+        assert!(matches!(
+            AerodynamicTable::from_csv("spin_factor,reynolds,seam_angle,cl,cd\n0,100000,0,0.2"),
+            Err(AerodynamicTableError::Malformed { line: 2, .. })
+        ));
+        assert!(matches!(
+            AerodynamicTable::from_csv("spin_factor,reynolds,seam_angle,cl,cd\n0,fast,0,0.2,0.29"),
+            Err(AerodynamicTableError::Malformed { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_non_monotonic_axis() {
+        let csv = "spin_factor,reynolds,seam_angle,cl,cd
+            0.2,100000,0,0.4,0.39
+            0,100000,0,0.2,0.29";
+        assert!(matches!(
+            AerodynamicTable::from_csv(csv),
+            Err(AerodynamicTableError::NonMonotonic {
+                axis: "spin_factor",
+                line: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_repeated_grid_point() {
+        let csv = "spin_factor,reynolds,seam_angle,cl,cd
+            0,100000,0,0.2,0.29
+            0,100000,0,0.2,0.29";
+        assert!(matches!(
+            AerodynamicTable::from_csv(csv),
+            Err(AerodynamicTableError::DuplicatePoint { line: 3 })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_missing_grid_point() {
+        let (csv, _last_row) = GRID.rsplit_once('\n').unwrap();
+        assert!(matches!(
+            AerodynamicTable::from_csv(csv),
+            Err(AerodynamicTableError::MissingPoints {
+                expected: 8,
+                found: 7
+            })
+        ));
+    }
+}
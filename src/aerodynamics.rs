@@ -0,0 +1,308 @@
+use crate::*;
+use std::sync::Arc;
+
+/// drag coefficient model
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DragModel {
+    /// the umba calculator's constant drag coefficient
+    Constant(f64),
+    /// Nathan/Adair style Cd(Re, S). the drag crisis is a logistic step from `cd_subcritical`
+    /// down to `cd_supercritical` around `critical_reynolds`, plus a term linear in spin factor
+    ReynoldsSpin {
+        cd_subcritical: f64,
+        cd_supercritical: f64,
+        critical_reynolds: f64,
+        // width of the drag crisis in Reynolds number
+        crisis_width: f64,
+        // Cd added per unit of spin factor
+        spin_coefficient: f64,
+    },
+}
+
+impl Default for DragModel {
+    fn default() -> Self {
+        Self::Constant(CD_CONST)
+    }
+}
+
+impl DragModel {
+    /// crisis between ~60 and ~90 mph, spin term from Nathan's trajectory calculator
+    pub fn reynolds_spin() -> Self {
+        Self::ReynoldsSpin {
+            cd_subcritical: 0.5,
+            cd_supercritical: 0.3,
+            critical_reynolds: 1.5e5,
+            crisis_width: 2e4,
+            spin_coefficient: 0.0292,
+        }
+    }
+
+    pub fn coefficient(&self, reynolds: f64, spin_factor: f64) -> f64 {
+        match *self {
+            Self::Constant(cd) => cd,
+            Self::ReynoldsSpin {
+                cd_subcritical,
+                cd_supercritical,
+                critical_reynolds,
+                crisis_width,
+                spin_coefficient,
+            } => {
+                let crisis = 1. / (1. + (-(reynolds - critical_reynolds) / crisis_width).exp());
+                cd_subcritical - (cd_subcritical - cd_supercritical) * crisis
+                    + spin_coefficient * spin_factor
+            }
+        }
+    }
+}
+
+/// what a coefficient model gets to see at every evaluation
+#[derive(Debug, Copy, Clone)]
+pub struct AerodynamicInputs {
+    // in ft/s
+    pub speed: f64,
+    // in rad/s
+    pub spin_rate: f64,
+    // r * w / v, with spin decay applied
+    pub spin_factor: f64,
+    pub reynolds: f64,
+    // seam orientation relative to the spin axis, in rad
+    pub seam_angle: f64,
+    pub time_elapsed: f64,
+}
+
+/// lift, drag and seam shifted wake coefficients of a published (or your own) model.
+/// set it for every ball through `BaseballPluginConfig::model`, or per ball by inserting an
+/// `AerodynamicModelHandle` on the entity
+pub trait AerodynamicModel: std::fmt::Debug + Send + Sync + 'static {
+    fn lift_coefficient(&self, inputs: &AerodynamicInputs) -> f64;
+
+    fn drag_coefficient(&self, inputs: &AerodynamicInputs) -> f64;
+
+    /// lift per active seam per unit length away from the origin
+    fn seam_coefficient(&self, _inputs: &AerodynamicInputs) -> f64 {
+        C_SEAMS
+    }
+}
+
+/// shared handle to an aerodynamic model. as a component it overrides the plugin's model for that ball
+#[derive(Debug, Clone, Component)]
+pub struct AerodynamicModelHandle(pub Arc<dyn AerodynamicModel>);
+
+impl AerodynamicModelHandle {
+    pub fn new(model: impl AerodynamicModel) -> Self {
+        Self(Arc::new(model))
+    }
+
+    pub fn get(&self) -> &dyn AerodynamicModel {
+        &*self.0
+    }
+}
+
+impl Default for AerodynamicModelHandle {
+    fn default() -> Self {
+        Self::new(UmbaModel::default())
+    }
+}
+
+/// the umba baseball flight calculator
+#[derive(Debug, Copy, Clone, Default)]
+pub struct UmbaModel {
+    pub drag: DragModel,
+}
+
+impl AerodynamicModel for UmbaModel {
+    fn lift_coefficient(&self, inputs: &AerodynamicInputs) -> f64 {
+        1. / (2.42 + (0.4 / inputs.spin_factor))
+    }
+
+    fn drag_coefficient(&self, inputs: &AerodynamicInputs) -> f64 {
+        self.drag.coefficient(inputs.reynolds, inputs.spin_factor)
+    }
+}
+
+/// Sawicki, Hubbard & Stronge, "How to hit home runs", Am. J. Phys. 71, 1152 (2003).
+/// bilinear lift with a drag crisis
+#[derive(Debug, Copy, Clone)]
+pub struct SawickiModel {
+    pub drag: DragModel,
+}
+
+impl Default for SawickiModel {
+    fn default() -> Self {
+        Self {
+            drag: DragModel::ReynoldsSpin {
+                cd_subcritical: 0.5,
+                cd_supercritical: 0.22,
+                critical_reynolds: 1.6e5,
+                crisis_width: 1.5e4,
+                spin_coefficient: 0.,
+            },
+        }
+    }
+}
+
+impl AerodynamicModel for SawickiModel {
+    fn lift_coefficient(&self, inputs: &AerodynamicInputs) -> f64 {
+        let s = inputs.spin_factor;
+        if s <= 0.1 {
+            1.5 * s
+        } else {
+            0.09 + 0.6 * s
+        }
+    }
+
+    fn drag_coefficient(&self, inputs: &AerodynamicInputs) -> f64 {
+        self.drag.coefficient(inputs.reynolds, inputs.spin_factor)
+    }
+}
+
+/// the lift fit of Nathan's trajectory calculator, after Nathan, "The effect of spin on the flight
+/// of a baseball", Am. J. Phys. 76, 119 (2008)
+#[derive(Debug, Copy, Clone)]
+pub struct Nathan2008Model {
+    pub drag: DragModel,
+}
+
+impl Default for Nathan2008Model {
+    fn default() -> Self {
+        Self {
+            drag: DragModel::Constant(0.35),
+        }
+    }
+}
+
+impl AerodynamicModel for Nathan2008Model {
+    fn lift_coefficient(&self, inputs: &AerodynamicInputs) -> f64 {
+        1. / (2.32 + (0.4 / inputs.spin_factor))
+    }
+
+    fn drag_coefficient(&self, inputs: &AerodynamicInputs) -> f64 {
+        self.drag.coefficient(inputs.reynolds, inputs.spin_factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-12;
+
+    fn inputs(spin_factor: f64) -> AerodynamicInputs {
+        AerodynamicInputs {
+            speed: 130.,
+            spin_rate: 230.,
+            spin_factor,
+            reynolds: 1.8e5,
+            seam_angle: 0.,
+            time_elapsed: 0.,
+        }
+    }
+
+    fn assert_lift(model: &dyn AerodynamicModel, curve: &[(f64, f64)]) {
+        for &(spin_factor, cl) in curve {
+            let lift = model.lift_coefficient(&inputs(spin_factor));
+            assert!(
+                (lift - cl).abs() < EPSILON,
+                "{model:?}: cl({spin_factor}) = {lift}, expected {cl}"
+            );
+        }
+    }
+
+    #[test]
+    fn umba_lift() {
+        // cl = 1 / (2.42 + 0.4 / s)
+        assert_lift(
+            &UmbaModel::default(),
+            &[(0.1, 1. / 6.42), (0.2, 1. / 4.42), (0.4, 1. / 3.42)],
+        );
+    }
+
+    #[test]
+    fn sawicki_lift_is_bilinear() {
+        // cl = 1.5 s up to s = 0.1, 0.09 + 0.6 s above
+        assert_lift(
+            &SawickiModel::default(),
+            &[(0.05, 0.075), (0.1, 0.15), (0.2, 0.21), (0.4, 0.33)],
+        );
+    }
+
+    #[test]
+    fn nathan_lift() {
+        // cl = 1 / (2.32 + 0.4 / s)
+        assert_lift(
+            &Nathan2008Model::default(),
+            &[(0.1, 1. / 6.32), (0.2, 1. / 4.32), (0.4, 1. / 3.32)],
+        );
+    }
+
+    #[test]
+    fn models_use_their_own_drag() {
+        let constant = UmbaModel {
+            drag: DragModel::Constant(0.42),
+        };
+        assert_eq!(constant.drag_coefficient(&inputs(0.2)), 0.42);
+        assert_eq!(
+            UmbaModel::default().drag_coefficient(&inputs(0.2)),
+            CD_CONST
+        );
+        assert_eq!(
+            Nathan2008Model::default().drag_coefficient(&inputs(0.2)),
+            0.35
+        );
+        // well past the crisis
+        let sawicki = SawickiModel::default().drag_coefficient(&AerodynamicInputs {
+            reynolds: 3e5,
+            ..inputs(0.2)
+        });
+        assert!((sawicki - 0.22).abs() < 1e-3, "{sawicki}");
+    }
+
+    // ft/s
+    const MPH: f64 = 5280. / 3600.;
+
+    #[test]
+    fn reynolds_spin_drag_crisis() {
+        let drag = DragModel::reynolds_spin();
+        let atmosphere = Atmosphere::default();
+        let cd = |mph: f64| drag.coefficient(atmosphere.reynolds_number(mph * MPH), 0.);
+
+        // subcritical for slow curveballs, supercritical for fastballs, halfway at the crisis
+        assert!(cd(50.) > 0.45, "{}", cd(50.));
+        assert!(cd(90.) < 0.32, "{}", cd(90.));
+        assert!(cd(110.) > 0.3);
+        assert!((drag.coefficient(1.5e5, 0.) - 0.4).abs() < EPSILON);
+        for mph in 40..110 {
+            assert!(cd(mph as f64 + 1.) < cd(mph as f64));
+        }
+    }
+
+    #[test]
+    fn reynolds_spin_drag_grows_with_spin() {
+        let drag = DragModel::reynolds_spin();
+        for spin_factor in [0.1, 0.2, 0.4] {
+            let added = drag.coefficient(1.8e5, spin_factor) - drag.coefficient(1.8e5, 0.);
+            assert!((added - 0.0292 * spin_factor).abs() < EPSILON);
+        }
+    }
+
+    #[test]
+    fn atmosphere_moves_the_drag_crisis() {
+        // the same pitch in more viscous air is at a lower reynolds number, before the crisis
+        let drag = DragModel::reynolds_spin();
+        let viscous = Atmosphere {
+            kinematic_viscosity: NU * 1.5,
+            ..default()
+        };
+        let speed = 80. * MPH;
+        assert!(
+            (viscous.reynolds_number(speed) * 1.5 - Atmosphere::default().reynolds_number(speed))
+                .abs()
+                < 1e-6
+        );
+        assert!(
+            drag.coefficient(viscous.reynolds_number(speed), 0.)
+                > drag.coefficient(Atmosphere::default().reynolds_number(speed), 0.)
+        );
+    }
+}
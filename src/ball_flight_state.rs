@@ -1,12 +1,40 @@
 use crate::*;
 
 #[derive(Debug, Component, Reflect, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BaseballFlightState {
     pub translation: DVec3,
     pub v: DVec3,
     pub spin: DVec3,
+    // spin perpendicular to the velocity, the part that produces magnus
+    pub active_spin: DVec3,
+    // spin along the velocity
+    pub gyro_spin: DVec3,
+    // |active_spin| / |spin|
+    pub spin_efficiency: f64,
+    // seam points in the ball's own frame, they never move. `seam_orientation` places them in
+    // the baseball coord, see `seam_points`.
+    // a vec since `SeamGeometry` can have any resolution. it's cloned from the geometry once at
+    // activation and never reallocated while the ball flies
     pub seams: Vec<DVec3>,
+    // seam length each seam point stands for, see `SeamGeometry`
+    pub seam_weights: Vec<f64>,
+    // rotation of the seam geometry in baseball coord, turns with the spin
+    pub seam_orientation: DQuat,
+    // seam_z_angle the ball was activated with, in rad
+    pub seam_angle: f64,
+    pub knuckleball_flutter: KnuckleballFlutter,
+    // forces at the end of the last update
+    pub force_breakdown: ForceBreakdown,
+    // set every update from `FlightLod` or `FlightLodPolicy`
+    pub lod: FlightLod,
     pub time_elapsed: f64,
+    // simulated time not yet covered by a whole integrator step, carried over to the next update
+    pub step_remainder: f64,
+    // next step size of the adaptive integrator, 0 until its first step
+    pub adaptive_step: f64,
+    // set once the ball has passed the front of `HomePlate`
+    pub crossed_plate: bool,
     //
     pub active: bool,
 }
@@ -15,6 +43,8 @@ impl BaseballFlightState {
     pub(crate) fn deactivate(&mut self) {
         self.active = false;
         self.time_elapsed = 0.;
+        self.step_remainder = 0.;
+        self.adaptive_step = 0.;
     }
 
     pub(crate) fn from_params(
@@ -24,58 +54,76 @@ impl BaseballFlightState {
         velocity_: DVec3,
         // spin in rads
         spin_: DVec3,
-        // in rad
-        seam_y_angle_: f32,
-        // in rad
-        seam_z_angle_: f32,
+        // rotation of the seam geometry in baseball coord
+        seam_orientation: DQuat,
+        geometry: &SeamGeometry,
         // other parameters...
     ) -> Self {
         let translation = translation_;
         let v = velocity_;
         let spin = spin_;
-        let seam_y_angle = seam_y_angle_ as f64;
-        let seam_z_angle = seam_z_angle_ as f64;
-
-        let seams = (0..N_SEAMS)
-            .map(|i| {
-                let alpha =
-                    (PI_64 * 2.) * (f64::from((i % N_SEAMS) as i16) / f64::from(N_SEAMS as i16));
-                let x = (1. / 13.) * (9. * f64::cos(alpha) - 4. * f64::cos(3. * alpha));
-                let y = (1. / 13.) * (9. * f64::sin(alpha) + 4. * f64::sin(3. * alpha));
-                let z = (12. / 13.) * f64::cos(2. * alpha);
-                DVec3::new(x, y, z) * (SEAM_DIAMETER / 2.)
-            })
-            .collect::<Vec<_>>();
 
-        let seams_adjsuted = seams
-            .iter()
-            .map(|point| {
-                // X axis of seams space should be the axis of rotation
-                DQuat::from_rotation_arc(DVec3::X, spin.normalize()).mul_vec3(
-                    DQuat::from_rotation_z(-seam_z_angle).mul_vec3(
-                        DQuat::from_rotation_y(seam_y_angle).mul_vec3(
-                            DQuat::from_rotation_y(PI_64 / 2.)
-                                .mul_vec3(DQuat::from_rotation_x(-PI_64 / 2.).mul_vec3(*point)),
-                        ),
-                    ),
-                )
-            })
-            .collect::<Vec<_>>();
-
-        Self {
+        let mut state = Self {
             translation,
             v,
             spin,
-            seams: seams_adjsuted,
+            seams: geometry.points.clone(),
+            seam_weights: geometry.weights.clone(),
+            seam_orientation,
+            seam_angle: SeamOrientation::angles_from_quat(spin, seam_orientation).1 as f64,
             time_elapsed: 0.,
+            step_remainder: 0.,
             active: true,
+            ..default()
+        };
+        state.update_spin_components();
+        state
+    }
+
+    /// seam points in the baseball coord
+    pub fn seam_points(&self) -> impl Iterator<Item = DVec3> + '_ {
+        self.seams
+            .iter()
+            .map(|point| self.seam_orientation.mul_vec3(*point))
+    }
+
+    /// false once the integration has produced a NaN or infinity
+    pub fn is_finite(&self) -> bool {
+        self.translation.is_finite()
+            && self.v.is_finite()
+            && self.spin.is_finite()
+            && self.seam_orientation.is_finite()
+    }
+
+    /// side of the ball the gyro component points out of, seen from behind the pitch
+    pub fn gyro_pole(&self) -> GyroPole {
+        if self.gyro_spin.dot(self.v) >= 0. {
+            GyroPole::Right
+        } else {
+            GyroPole::Left
         }
     }
 
-    // option 3
+    // the split between active and gyro spin changes as the velocity turns along the path
+    fn update_spin_components(&mut self) {
+        let direction = self.v.normalize_or_zero();
+        self.gyro_spin = direction * self.spin.dot(direction);
+        self.active_spin = self.spin - self.gyro_spin;
+        let spin_rate = self.spin.length();
+        self.spin_efficiency = if spin_rate > 0. {
+            self.active_spin.length() / spin_rate
+        } else {
+            0.
+        };
+    }
+
+    // FlightIntegrationMode::ForceDriven
+    // returns the acceleration (gravity included) the internal integrator averaged over
+    // the steps it took, so the avian body follows the same path as the internal state
     pub(crate) fn update_state_and_get_acceleration(
         &mut self,
         config: &BaseballPluginConfig,
+        model: &dyn AerodynamicModel,
         translation: DVec3,
         velocity: DVec3,
         delta_t: f64,
@@ -83,166 +131,398 @@ impl BaseballFlightState {
         self.translation = translation;
         self.v = velocity;
 
-        self.update_state(config, delta_t);
+        let stepped = self.update_state(config, model, delta_t);
 
-        let distance = self.translation - translation;
+        let a = if stepped > 0. {
+            (self.v - velocity) / stepped
+        } else {
+            // frame shorter than a step, use the current acceleration instead
+            self.acceleration(config, model)
+        };
 
-        (self.v * self.v - velocity * velocity) / (2. * distance)
+        if a.is_finite() {
+            a
+        } else {
+            DVec3::ZERO
+        }
     }
 
-    // option 2
+    // FlightIntegrationMode::VelocityDriven
     pub(crate) fn update_state_and_get_velo(
         &mut self,
         config: &BaseballPluginConfig,
+        model: &dyn AerodynamicModel,
         translation: DVec3,
         delta_t: f64,
     ) -> DVec3 {
         self.translation = translation;
 
-        self.update_state(config, delta_t);
+        let stepped = self.update_state(config, model, delta_t);
+
+        if stepped > 0. {
+            (self.translation - translation) / stepped
+        } else {
+            self.v
+        }
+    }
+
+    // FlightIntegrationMode::Kinematic and FlightIntegrationMode::Internal
+    // returns the simulated time, in s
+    pub(crate) fn update_state(
+        &mut self,
+        config: &BaseballPluginConfig,
+        model: &dyn AerodynamicModel,
+        delta_t: f64,
+    ) -> f64 {
+        let stepped = self.integrate(config, model, delta_t);
+        if stepped > 0. {
+            self.force_breakdown =
+                self.force_breakdown_at(config, model, &self.flight_vector(), self.time_elapsed);
+        }
+        stepped
+    }
+
+    fn integrate(
+        &mut self,
+        config: &BaseballPluginConfig,
+        model: &dyn AerodynamicModel,
+        delta_t: f64,
+    ) -> f64 {
+        // a bad integrator would never finish the update, see `BaseballFlightPlugin::integrator`
+        if config.integrator.validate().is_err() {
+            return 0.;
+        }
+        match config.integrator {
+            Integrator::Rk4 { step } => {
+                // run whole steps only and keep the leftover for the next update, so the
+                // trajectory doesn't depend on the fixed timestep rate or physics speed
+                self.step_remainder += delta_t;
+                let iterations = (self.step_remainder / step + STEP_TOLERANCE).floor() as usize;
+                self.step_remainder = (self.step_remainder - iterations as f64 * step).max(0.);
+
+                if self.lod == FlightLod::Analytic {
+                    self.update_state_analytic(config, model, iterations as f64 * step);
+                } else {
+                    for _ in 0..iterations {
+                        self.step_rk4(config, model, step);
+                    }
+                }
+                iterations as f64 * step
+            }
+            Integrator::DormandPrince {
+                relative_tolerance,
+                absolute_tolerance,
+                min_step,
+                max_step,
+            } => {
+                if self.lod == FlightLod::Analytic {
+                    self.update_state_analytic(config, model, delta_t);
+                    return delta_t.max(0.);
+                }
+
+                // like rk4, only whole steps are flown and the leftover waits for the next
+                // update, so the steps don't depend on the update rate either
+                self.step_remainder += delta_t;
+                let mut stepped = 0.;
+                loop {
+                    let h = if self.adaptive_step > 0. {
+                        self.adaptive_step.clamp(min_step, max_step)
+                    } else {
+                        max_step
+                    };
+                    if h > self.step_remainder + STEP_TOLERANCE * min_step {
+                        break;
+                    }
+
+                    let (y, error) = self.dormand_prince(
+                        config,
+                        model,
+                        h,
+                        relative_tolerance,
+                        absolute_tolerance,
+                    );
+
+                    // a non finite error can't be improved on by shrinking the step
+                    if error <= 1. || h <= min_step || !error.is_finite() {
+                        self.set_flight_vector(y);
+                        self.time_elapsed += h;
+                        self.update_spin_components();
+                        self.step_remainder -= h;
+                        stepped += h;
+                    }
+
+                    let factor = if error > 0. {
+                        (0.9 * error.powf(-0.2)).clamp(0.2, 5.)
+                    } else {
+                        5.
+                    };
+                    self.adaptive_step = (h * factor).clamp(min_step, max_step);
+                }
+                self.step_remainder = self.step_remainder.max(0.);
+                stepped
+            }
+        }
+    }
 
-        (self.translation - translation) / delta_t
+    fn flight_vector(&self) -> FlightVector {
+        FlightVector {
+            translation: self.translation,
+            v: self.v,
+            spin: self.spin,
+            orientation: self.seam_orientation,
+        }
     }
 
-    // option 1
-    pub(crate) fn update_state(&mut self, config: &BaseballPluginConfig, delta_t: f64) {
-        let iterations = (delta_t * 1000.).floor() as usize;
+    fn set_flight_vector(&mut self, y: FlightVector) {
+        self.translation = y.translation;
+        self.v = y.v;
+        self.spin = y.spin;
+        self.seam_orientation = y.orientation;
+    }
 
-        for _ in 0..iterations {
-            // rotate seams
-            self.seams = self
-                .seams
-                .iter()
-                .map(|point| {
-                    // in seam space, the seams are rotating around the local x axis
-                    DQuat::from_axis_angle(self.spin.normalize(), self.spin.length() * T_STEP)
-                        .mul_vec3(*point)
-                })
-                .collect::<Vec<_>>();
+    // position, velocity, spin and seams all move through the four stages, so ssw is
+    // re-evaluated with the seams where each stage puts them
+    fn step_rk4(&mut self, config: &BaseballPluginConfig, model: &dyn AerodynamicModel, h: f64) {
+        let y = self.flight_vector();
+        let t = self.time_elapsed;
 
-            let active_seams = self.find_ssw_seams(&config.ssw);
+        let k_1 = self.flight_derivative(config, model, &y, t);
+        let k_2 = self.flight_derivative(config, model, &y.advance(&k_1, h * 0.5), t + h * 0.5);
+        let k_3 = self.flight_derivative(config, model, &y.advance(&k_2, h * 0.5), t + h * 0.5);
+        let k_4 = self.flight_derivative(config, model, &y.advance(&k_3, h), t + h);
 
-            let a = self.rk4(config, &active_seams);
+        let slope = (k_1 + (k_2 + k_3) * 2. + k_4) * (1. / 6.);
 
-            self.time_elapsed += T_STEP;
+        self.set_flight_vector(y.advance(&slope, h));
+        self.time_elapsed += h;
+        self.update_spin_components();
+    }
 
-            self.v += DVec3::new(a.x, a.y, a.z - 32.2) * T_STEP;
-            self.translation += self.v * T_STEP;
+    // FlightLod::Analytic, the whole update at the acceleration from its start
+    fn update_state_analytic(
+        &mut self,
+        config: &BaseballPluginConfig,
+        model: &dyn AerodynamicModel,
+        delta_t: f64,
+    ) {
+        if delta_t <= 0. {
+            return;
         }
+        let a = self.acceleration(config, model);
+
+        self.seam_orientation =
+            (DQuat::from_scaled_axis(self.spin * delta_t) * self.seam_orientation).normalize();
+        self.time_elapsed += delta_t;
+
+        self.translation += self.v * delta_t + a * (0.5 * delta_t * delta_t);
+        self.v += a * delta_t;
+
+        self.spin *= config.spin_decay.factor(self.v.length(), delta_t);
+        self.update_spin_components();
+    }
+
+    /// instantaneous acceleration at the current state, gravity included
+    pub(crate) fn acceleration(
+        &self,
+        config: &BaseballPluginConfig,
+        model: &dyn AerodynamicModel,
+    ) -> DVec3 {
+        self.derivs(config, model, &self.flight_vector(), self.time_elapsed) - DVec3::Z * GRAVITY
+    }
+
+    // weighted sum of the seams that affect ssw at `y`, in baseball coord.
+    // zero when ssw doesn't apply, so the seams aren't searched for nothing
+    fn ssw_seam_sum(&self, config: &BaseballPluginConfig, y: &FlightVector) -> DVec3 {
+        if !config.ssw_on || self.lod != FlightLod::Full || y.v.length() < MIN_SPEED {
+            return DVec3::ZERO;
+        }
+        let body_sum = self.ssw_seams(&config.ssw, *y).fold(DVec3::ZERO, |sum, i| {
+            sum + self.seams[i] * self.seam_weights[i]
+        });
+        y.orientation.mul_vec3(body_sum)
     }
 
     // find seam indices that affect ssw
     // note that the local x-axis of the seams is the rotational axis
-    // we need to calculate the velocity vector in relation to the seams' local bases
-    fn find_ssw_seams(&self, ssw: &SeamShiftedWake) -> Vec<usize> {
-        // let v_adjusted = self.in_seam_space(self.v);
-        let rot_v = DQuat::from_rotation_arc(-DVec3::Y, self.v.normalize());
-        let rot_spin = DQuat::from_rotation_x(ssw.seam_shift_factor * T_STEP);
+    // the seams stay in the body frame, so the activation region and the flow are brought into
+    // it once per call instead of moving every point
+    fn ssw_seams<'a>(
+        &'a self,
+        ssw: &'a SeamShiftedWake,
+        y: FlightVector,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let direction = y.v.normalize_or_zero();
+        // fixed angles, so the active seams don't depend on the integrator's step
+        let mut rot_spin = DQuat::from_rotation_x(ssw.seam_shift_factor * SEAM_SHIFT_TIME);
+        if ssw.gyro_seam_shift {
+            // gyro spin turns the seams around the flow axis, which shifts where the flow separates
+            let gyro_rate = y.spin.dot(direction);
+            rot_spin = DQuat::from_axis_angle(
+                direction,
+                ssw.seam_shift_factor * gyro_rate * SEAM_SHIFT_TIME,
+            ) * rot_spin;
+        }
+        let to_region = rot_spin.inverse() * y.orientation;
+        let direction_body = y.orientation.inverse().mul_vec3(direction);
         let (max, min) = ssw.get_activation_region();
 
-        (0..N_SEAMS)
-            .filter(|&i| {
-                let point_adjusted = rot_v.mul_vec3(
-                    rot_v
-                        .inverse()
-                        .mul_vec3(rot_spin.inverse().mul_vec3(self.seams[i])),
-                );
-                if (point_adjusted.x < max.x)
-                    && (point_adjusted.x > min.x)
-                    // within y range
-                    && (point_adjusted.y < max.y)
-                    && (point_adjusted.y > min.y)
-                    // within z range
-                    && (point_adjusted.z < max.z)
-                    && (point_adjusted.z > min.z)
-                {
-                    self.outside_separated_flow(ssw, i)
-                } else {
-                    false
-                }
-            })
-            .collect::<Vec<_>>()
+        (0..self.seams.len()).filter(move |&i| {
+            let point_adjusted = to_region.mul_vec3(self.seams[i]);
+            (point_adjusted.x < max.x)
+                && (point_adjusted.x > min.x)
+                // within y range
+                && (point_adjusted.y < max.y)
+                && (point_adjusted.y > min.y)
+                // within z range
+                && (point_adjusted.z < max.z)
+                && (point_adjusted.z > min.z)
+                && self.outside_separated_flow(ssw, direction_body, i)
+        })
     }
 
     /// since seams in the activation region cannot cause a separated flow to
-    /// become separated again this function will eliminate any inline seams
-    fn outside_separated_flow(&self, ssw: &SeamShiftedWake, index: usize) -> bool {
+    /// become separated again this function will eliminate any inline seams.
+    /// `direction` is the flow direction in the body frame
+    fn outside_separated_flow(
+        &self,
+        ssw: &SeamShiftedWake,
+        direction: DVec3,
+        index: usize,
+    ) -> bool {
         let point = &self.seams[index];
-        let next_point = &self.seams[(index + 1) % N_SEAMS];
-        let prev_point = &self.seams[(index + N_SEAMS - 1) % N_SEAMS];
-        let normalized_v: &DVec3 = &self.v.normalize();
-
-        let angle_d = normalized_v.dot((*point - *prev_point).normalize()).acos();
-        let angle_u = normalized_v.dot((*next_point - *point).normalize()).acos();
+        let n_seams = self.seams.len();
+        let next_point = &self.seams[(index + 1) % n_seams];
+        let prev_point = &self.seams[(index + n_seams - 1) % n_seams];
+
+        // repeated points leave a segment without direction, it counts as across the flow
+        let angle_d = direction
+            .dot((*point - *prev_point).normalize_or_zero())
+            .clamp(-1., 1.)
+            .acos();
+        let angle_u = direction
+            .dot((*next_point - *point).normalize_or_zero())
+            .clamp(-1., 1.)
+            .acos();
 
         (angle_d - PI_64).abs() >= ssw.separated_flow_range
             && (angle_u - PI_64).abs() >= ssw.separated_flow_range
     }
 
-    fn rk4(&self, config: &BaseballPluginConfig, active_seams: &Vec<usize>) -> DVec3 {
-        let spin = &self.spin;
-        let seams = &self.seams;
-        let time_elapsed = self.time_elapsed as f64;
-
-        let v_1 = self.v;
-        let t_1 = time_elapsed;
-        let a_1 = Self::derivs(config, &v_1, spin, seams, t_1, active_seams);
-
-        let v_2 = v_1 + a_1 * T_STEP * 0.5;
-        let t_2 = t_1 + T_STEP * 0.5;
-        let a_2 = Self::derivs(config, &v_2, spin, seams, t_2, active_seams);
-
-        let v_3 = v_2 + a_2 * T_STEP * 0.5;
-        let t_3 = t_2 + T_STEP * 0.5;
-        let a_3 = Self::derivs(config, &v_3, spin, seams, t_3, active_seams);
+    // one dormand-prince step of h, returns the 5th order state and the error norm of
+    // position and velocity, which is <= 1 within tolerance
+    fn dormand_prince(
+        &self,
+        config: &BaseballPluginConfig,
+        model: &dyn AerodynamicModel,
+        h: f64,
+        relative_tolerance: f64,
+        absolute_tolerance: f64,
+    ) -> (FlightVector, f64) {
+        let y = self.flight_vector();
+        let mut k = [FlightVector::ZERO; 7];
+        for stage in 0..7 {
+            let slope = FlightVector::weighted_sum(&k[..stage], &DP_A[stage]);
+            k[stage] = self.flight_derivative(
+                config,
+                model,
+                &y.advance(&slope, h),
+                self.time_elapsed + DP_C[stage] * h,
+            );
+        }
 
-        let v_4 = v_3 + a_3 * T_STEP;
-        let t_4 = t_3 + T_STEP;
-        let a_4 = Self::derivs(config, &v_4, spin, seams, t_4, active_seams);
+        let y_next = y.advance(&FlightVector::weighted_sum(&k, &DP_B), h);
+        let error = FlightVector::weighted_sum(&k, &DP_E) * h;
 
-        let slope = (a_1 + 2. * (a_2 + a_3) + a_4) / 6.0;
+        let scaled_error = |error: DVec3, y_0: DVec3, y_1: DVec3| {
+            error.length()
+                / (absolute_tolerance + relative_tolerance * y_0.length().max(y_1.length()))
+        };
+        let error = scaled_error(error.translation, y.translation, y_next.translation)
+            .max(scaled_error(error.v, y.v, y_next.v));
 
-        // if self.time_elapsed > 0. && self.time_elapsed < 0.012 {
-        //     info!("a_1 {:?}", a_1);
-        //     info!("a_2 {:?}", a_2);
-        //     info!("a_3 {:?}", a_3);
-        //     info!("a_4 {:?}", a_4);
-        // }
+        (y_next, error)
+    }
 
-        slope
+    // time derivative of the whole flight state at `y`, gravity included
+    fn flight_derivative(
+        &self,
+        config: &BaseballPluginConfig,
+        model: &dyn AerodynamicModel,
+        y: &FlightVector,
+        time_elapsed: f64,
+    ) -> FlightVector {
+        FlightVector {
+            translation: y.v,
+            v: self.derivs(config, model, y, time_elapsed) - DVec3::Z * GRAVITY,
+            spin: y.spin * config.spin_decay.rate_at(y.v.length()),
+            // dq/dt = 1/2 w q
+            orientation: DQuat::from_xyzw(y.spin.x, y.spin.y, y.spin.z, 0.) * y.orientation * 0.5,
+        }
     }
 
+    // aerodynamic acceleration at `y`
     fn derivs(
+        &self,
         config: &BaseballPluginConfig,
-        v: &DVec3,
-        spin: &DVec3,
-        seams: &Vec<DVec3>,
+        model: &dyn AerodynamicModel,
+        y: &FlightVector,
         time_elapsed: f64,
-        active_seams: &Vec<usize>,
     ) -> DVec3 {
+        self.force_breakdown_at(config, model, y, time_elapsed)
+            .aerodynamic()
+    }
+
+    fn force_breakdown_at(
+        &self,
+        config: &BaseballPluginConfig,
+        model: &dyn AerodynamicModel,
+        y: &FlightVector,
+        time_elapsed: f64,
+    ) -> ForceBreakdown {
+        let v = &y.v;
+        let spin = &y.spin;
         let v_tot = v.length();
         let spin_rate = spin.length();
+        let gravity = -DVec3::Z * GRAVITY;
+
+        // no flow over a ball that isn't moving
+        if v_tot < MIN_SPEED {
+            return ForceBreakdown {
+                gravity,
+                ..default()
+            };
+        }
+
+        let c_0 = config.atmosphere.c_0();
 
         let rw = (DIAMETER / 2.) * spin_rate;
-        let s = (rw / v_tot) * (-time_elapsed / SPIN_DECAY).exp();
-        let cl = 1. / (2.42 + (0.4 / s));
+        let s = rw / v_tot;
+        let inputs = AerodynamicInputs {
+            speed: v_tot,
+            spin_rate,
+            spin_factor: s,
+            reynolds: config.atmosphere.reynolds_number(v_tot),
+            seam_angle: self.seam_angle,
+            time_elapsed,
+        };
+        let cl = model.lift_coefficient(&inputs);
 
         // drag force
         let a_drag = if config.drag_on {
-            *v * -C_0 * CD_CONST * v_tot
+            *v * -c_0 * model.drag_coefficient(&inputs) * v_tot
         } else {
             DVec3::ZERO
         };
 
         // magnus force
-        let a_spin = if config.magnus_on {
+        let a_spin = if config.magnus_on && spin_rate >= MIN_SPIN_RATE {
             let [u, v, w] = v.to_array();
             let [spin_x, spin_y, spin_z] = spin.to_array();
             DVec3::new(
                 spin_y * w - spin_z * v,
                 spin_z * u - spin_x * w,
                 spin_x * v - spin_y * u,
-            ) * C_0
+            ) * c_0
                 * (cl / spin_rate)
                 * v_tot
         } else {
@@ -250,15 +530,333 @@ impl BaseballFlightState {
         };
 
         // ssw
-        let a_ssw = if config.ssw_on {
-            let seams_length = active_seams
-                .iter()
-                .fold(DVec3::ZERO, |s_length, &i| s_length + seams[i]);
-            seams_length * -C_0 * C_SEAMS * v_tot.powi(2)
+        let a_ssw = if config.ssw_on && self.lod == FlightLod::Full {
+            self.ssw_seam_sum(config, y) * -c_0 * model.seam_coefficient(&inputs) * v_tot.powi(2)
         } else {
             DVec3::ZERO
         };
 
-        a_drag + a_spin + a_ssw
+        // knuckleball
+        let a_knuckleball = if config.knuckleball.enabled
+            && self.lod == FlightLod::Full
+            && spin_rate < config.knuckleball.spin_threshold
+        {
+            config.knuckleball.force(
+                &self.knuckleball_flutter,
+                v.normalize_or_zero(),
+                &self.seams,
+                &self.seam_weights,
+                y.orientation,
+                time_elapsed,
+            ) * c_0
+                * v_tot.powi(2)
+        } else {
+            DVec3::ZERO
+        };
+
+        ForceBreakdown {
+            drag: a_drag,
+            magnus: a_spin,
+            ssw: a_ssw,
+            knuckleball: a_knuckleball,
+            gravity,
+        }
+    }
+
+    // seam indices that affect ssw at the current state
+    #[cfg(feature = "debug-gizmos")]
+    pub(crate) fn active_ssw_seams<'a>(
+        &'a self,
+        ssw: &'a SeamShiftedWake,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.ssw_seams(ssw, self.flight_vector())
+    }
+}
+
+/// accelerations acting on the ball, in ft/s^2 and baseball coord
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ForceBreakdown {
+    pub drag: DVec3,
+    pub magnus: DVec3,
+    pub ssw: DVec3,
+    pub knuckleball: DVec3,
+    pub gravity: DVec3,
+}
+
+impl ForceBreakdown {
+    pub fn aerodynamic(&self) -> DVec3 {
+        self.drag + self.magnus + self.ssw + self.knuckleball
+    }
+
+    pub fn total(&self) -> DVec3 {
+        self.aerodynamic() + self.gravity
+    }
+}
+
+// the integrated part of the flight state, also used for its time derivative
+#[derive(Debug, Clone, Copy)]
+struct FlightVector {
+    translation: DVec3,
+    v: DVec3,
+    spin: DVec3,
+    // not normalized as a derivative
+    orientation: DQuat,
+}
+
+impl FlightVector {
+    const ZERO: Self = Self {
+        translation: DVec3::ZERO,
+        v: DVec3::ZERO,
+        spin: DVec3::ZERO,
+        orientation: DQuat::from_xyzw(0., 0., 0., 0.),
+    };
+
+    // a step of h along `slope`, keeping the orientation a rotation
+    fn advance(&self, slope: &Self, h: f64) -> Self {
+        Self {
+            translation: self.translation + slope.translation * h,
+            v: self.v + slope.v * h,
+            spin: self.spin + slope.spin * h,
+            orientation: (self.orientation + slope.orientation * h).normalize(),
+        }
+    }
+
+    fn weighted_sum(k: &[Self], weights: &[f64]) -> Self {
+        k.iter()
+            .zip(weights)
+            .fold(Self::ZERO, |sum, (k, weight)| sum + *k * *weight)
+    }
+}
+
+impl std::ops::Add for FlightVector {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            translation: self.translation + other.translation,
+            v: self.v + other.v,
+            spin: self.spin + other.spin,
+            orientation: self.orientation + other.orientation,
+        }
+    }
+}
+
+impl std::ops::Mul<f64> for FlightVector {
+    type Output = Self;
+
+    fn mul(self, scale: f64) -> Self {
+        Self {
+            translation: self.translation * scale,
+            v: self.v * scale,
+            spin: self.spin * scale,
+            orientation: self.orientation * scale,
+        }
+    }
+}
+
+// dormand-prince 5(4) tableau
+const DP_C: [f64; 7] = [0., 1. / 5., 3. / 10., 4. / 5., 8. / 9., 1., 1.];
+const DP_A: [[f64; 7]; 7] = [
+    [0.; 7],
+    [1. / 5., 0., 0., 0., 0., 0., 0.],
+    [3. / 40., 9. / 40., 0., 0., 0., 0., 0.],
+    [44. / 45., -56. / 15., 32. / 9., 0., 0., 0., 0.],
+    [
+        19372. / 6561.,
+        -25360. / 2187.,
+        64448. / 6561.,
+        -212. / 729.,
+        0.,
+        0.,
+        0.,
+    ],
+    [
+        9017. / 3168.,
+        -355. / 33.,
+        46732. / 5247.,
+        49. / 176.,
+        -5103. / 18656.,
+        0.,
+        0.,
+    ],
+    DP_B,
+];
+// 5th order weights
+const DP_B: [f64; 7] = [
+    35. / 384.,
+    0.,
+    500. / 1113.,
+    125. / 192.,
+    -2187. / 6784.,
+    11. / 84.,
+    0.,
+];
+// 5th minus 4th order weights
+const DP_E: [f64; 7] = [
+    71. / 57600.,
+    0.,
+    -71. / 16695.,
+    71. / 1920.,
+    -17253. / 339200.,
+    22. / 525.,
+    -1. / 40.,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ssw acceleration at release for every seam orientation on a coarse grid
+    fn ssw_forces(spin: DVec3, gyro_seam_shift: bool) -> Vec<DVec3> {
+        ssw_forces_with(
+            spin,
+            &BaseballPluginConfig {
+                ssw: SeamShiftedWake {
+                    gyro_seam_shift,
+                    ..default()
+                },
+                ..default()
+            },
+        )
+    }
+
+    fn ssw_forces_with(spin: DVec3, config: &BaseballPluginConfig) -> Vec<DVec3> {
+        let v = DVec3::new(0., -130., 0.);
+        (0..8)
+            .flat_map(|i| (0..8).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let (y, z) = (i as f32 * PI_32 / 8., j as f32 * PI_32 / 8.);
+                let state = BaseballFlightState::from_params(
+                    DVec3::ZERO,
+                    v,
+                    spin,
+                    SeamOrientation::quat_from_angles(spin, y, z),
+                    &config.seam_geometry,
+                );
+                state
+                    .force_breakdown_at(config, config.model.get(), &state.flight_vector(), 0.)
+                    .ssw
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gyro_spin_shifts_the_ssw_seams() {
+        // half backspin, half gyro along the flight
+        let spin = DVec3::new(-150., -150., 0.);
+        assert_ne!(ssw_forces(spin, true), ssw_forces(spin, false));
+    }
+
+    #[test]
+    fn gyro_seam_shift_leaves_pure_backspin_alone() {
+        let spin = DVec3::new(-230., 0., 0.);
+        assert_eq!(ssw_forces(spin, true), ssw_forces(spin, false));
+    }
+
+    #[test]
+    fn ssw_does_not_depend_on_the_seam_resolution() {
+        let spin = DVec3::new(-230., 0., 0.);
+        let at = |resolution| {
+            ssw_forces_with(
+                spin,
+                &BaseballPluginConfig {
+                    seam_geometry: SeamGeometry::from_curve(SeamCurve::TennisBall, resolution),
+                    ..default()
+                },
+            )
+        };
+        let (coarse, fine) = (at(N_SEAMS), at(N_SEAMS * 4));
+        // unweighted, four times the points would give about four times the force. what's left
+        // is the seams moving in and out of the separation band between the two samplings
+        let scale = coarse.iter().map(|force| force.length()).fold(0., f64::max);
+        for (coarse, fine) in coarse.iter().zip(&fine) {
+            assert!(coarse.distance(*fine) < 0.15 * scale, "{coarse} vs {fine}");
+        }
+        let total = |forces: &[DVec3]| forces.iter().map(|force| force.length()).sum::<f64>();
+        assert!((total(&fine) / total(&coarse) - 1.).abs() < 0.05);
+    }
+
+    #[test]
+    fn spin_decays_with_the_distance_flown() {
+        let fly = |spin_decay: SpinDecay| {
+            let config = BaseballPluginConfig {
+                spin_decay,
+                ..default()
+            };
+            let spin = DVec3::new(-230., 0., 0.);
+            let mut state = BaseballFlightState::from_params(
+                DVec3::ZERO,
+                DVec3::new(0., -130., 0.),
+                spin,
+                DQuat::IDENTITY,
+                &config.seam_geometry,
+            );
+            // dw/dt = -rate * (v / r) * w, so ln(w / w_0) = -rate / r * distance flown
+            let mut distance = 0.;
+            for _ in 0..30 {
+                let speed = state.v.length();
+                state.update_state(&config, config.model.get(), 1. / 60.);
+                distance += 0.5 * (speed + state.v.length()) / 60.;
+            }
+            (spin, state.spin, distance)
+        };
+
+        let (spin, decayed, distance) = fly(SpinDecay::default());
+        let expected = (-SpinDecay::default().rate / (DIAMETER / 2.) * distance).exp();
+        assert!((decayed.length() / spin.length() - expected).abs() < 1e-6);
+        assert!(decayed.normalize().abs_diff_eq(spin.normalize(), 1e-12));
+
+        let (spin, kept, _) = fly(SpinDecay { rate: 0. });
+        assert_eq!(kept, spin);
+    }
+
+    #[test]
+    fn spin_decay_time_constant_is_nathans() {
+        // ~46 s at 90 mph
+        let time_constant = -1. / SpinDecay::default().rate_at(132.);
+        assert!((time_constant - 46.).abs() < 1., "{time_constant}");
+    }
+
+    #[test]
+    fn rk4_stages_see_their_own_seams() {
+        let config = BaseballPluginConfig::default();
+        let model = config.model.get();
+        let spin = DVec3::new(-230., 0., 0.);
+        let mut state = BaseballFlightState::from_params(
+            DVec3::ZERO,
+            DVec3::new(0., -130., 0.),
+            spin,
+            SeamOrientation::quat_from_angles(spin, PI_32 / 4., PI_32 / 8.),
+            &config.seam_geometry,
+        );
+        // the seams turn ~0.6 rad over each half step
+        let h = 0.005;
+        let y = state.flight_vector();
+        assert_ne!(
+            state.ssw_seam_sum(&config, &y),
+            state.ssw_seam_sum(
+                &config,
+                &y.advance(&state.flight_derivative(&config, model, &y, 0.), h * 0.5)
+            ),
+        );
+
+        // the same step with every stage's ssw taken from the seams where the step started
+        let frozen = |k: &FlightVector, dt: f64| FlightVector {
+            orientation: y.orientation,
+            ..y.advance(k, dt)
+        };
+        let k_1 = state.flight_derivative(&config, model, &y, 0.);
+        let k_2 = state.flight_derivative(&config, model, &frozen(&k_1, h * 0.5), h * 0.5);
+        let k_3 = state.flight_derivative(&config, model, &frozen(&k_2, h * 0.5), h * 0.5);
+        let k_4 = state.flight_derivative(&config, model, &frozen(&k_3, h), h);
+        let frozen_v = y.v + (k_1.v + (k_2.v + k_3.v) * 2. + k_4.v) * (h / 6.);
+
+        state.step_rk4(&config, model, h);
+        assert!(
+            state.v.distance(frozen_v) > 1e-6,
+            "{}",
+            state.v.distance(frozen_v)
+        );
     }
 }
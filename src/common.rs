@@ -12,23 +12,31 @@ pub(crate) mod constants {
 
     pub(crate) const MASS: f32 = 0.145; // in kg
     pub(crate) const RADIUS: f32 = 0.037; // in m
-    pub(crate) const SEAM_R: f32 = (2. + 15. / 16.) / 2.; // in m
 
     // in pounds and ft/s
     pub(crate) const RHO: f64 = 0.074;
+    pub(crate) const GRAVITY: f64 = 32.2;
     // const CIRC: f64 = 9.125 / 12.;
     pub(crate) const T_STEP: f64 = 0.001;
+    // the ssw seam shift is `seam_shift_factor` times this, in s. it's the step the factor was
+    // tuned at and stays fixed whatever step the integrator takes
+    pub(crate) const SEAM_SHIFT_TIME: f64 = 0.001;
+    // absorbs float error when a frame is an exact multiple of T_STEP (e.g. 0.02 / 0.001)
+    pub(crate) const STEP_TOLERANCE: f64 = 1e-6;
+    // below these the flow or spin direction is undefined and the forces that need it are skipped
+    pub(crate) const MIN_SPEED: f64 = 1e-3; // in ft/s
+    pub(crate) const MIN_SPIN_RATE: f64 = 1e-3; // in rad/s
     pub(crate) const N_SEAMS: usize = 108;
     pub(crate) const DIAMETER: f64 = (2. + 15. / 16.) / 12.;
-    const MASS_OZ: f64 = 0.3203125;
-    const AREA: f64 = 0.25 * PI_64 * DIAMETER * DIAMETER;
-    pub(crate) const C_0: f64 = 0.5 * RHO * AREA / MASS_OZ;
+    pub(crate) const MASS_OZ: f64 = 0.3203125;
+    pub(crate) const AREA: f64 = 0.25 * PI_64 * DIAMETER * DIAMETER;
+    // kinematic viscosity of air at ~70F in ft^2/s
+    pub(crate) const NU: f64 = 1.6e-4;
     pub(crate) const CD_CONST: f64 = 0.33; // drag coefficient
     pub(crate) const C_SEAMS: f64 = 0.02; // The coefficient of Seams "Cseams" is the essentially the Lift coeficient
                                           // per seam per length away from the origin.
 
     pub(crate) const SEAM_DIAMETER: f64 = 2. + 15. / 16.;
-    pub(crate) const SPIN_DECAY: f64 = 10000.; // natural spin decay should be a large value
 }
 
 pub(crate) mod utils {
@@ -42,6 +50,32 @@ pub(crate) mod utils {
         DVec3::new(-vec.x, vec.z, vec.y) // maybe should consider changing the units as well?
     }
 
+    // the swap is a proper rotation (det = 1) and its own inverse, so rotations convert as M R M
+    fn swap_coordinates_dmat3() -> DMat3 {
+        DMat3::from_cols(DVec3::new(-1., 0., 0.), DVec3::Z, DVec3::Y)
+    }
+
+    pub fn rotation_from_bevy_to_baseball_coord(rotation: Quat) -> DQuat {
+        let swap = swap_coordinates_dmat3();
+        DQuat::from_mat3(&(swap * DMat3::from_quat(rotation.as_dquat()) * swap)).normalize()
+    }
+
+    pub fn rotation_from_baseball_coord_to_bevy(rotation: DQuat) -> Quat {
+        let swap = swap_coordinates_dmat3();
+        DQuat::from_mat3(&(swap * DMat3::from_quat(rotation) * swap))
+            .normalize()
+            .as_quat()
+    }
+
+    // small seedable generator, so seeded behaviour doesn't depend on an rng crate's version
+    pub(crate) fn split_mix_64(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
     pub fn kg_to_pound(weight: f32) -> f32 {
         weight * KG_TO_IBS
     }
@@ -50,6 +84,8 @@ pub(crate) mod utils {
         weight / KG_TO_IBS
     }
 
+    // converts the value it's called on, the names say which way
+    #[allow(clippy::wrong_self_convention)]
     pub trait BaseballCoordinateSystem {
         fn from_bevy_to_baseball_coord(&self) -> Self;
         fn from_baseball_coord_to_bevy(&self) -> Self;
@@ -25,3 +25,20 @@ impl Default for BaseballFlightBundle {
         }
     }
 }
+
+impl BaseballFlightBundle {
+    /// for `FlightIntegrationMode::Kinematic`
+    pub fn kinematic() -> Self {
+        Self {
+            rigid_body: RigidBody::Kinematic,
+            ..default()
+        }
+    }
+}
+
+/// marks the rendered ball, a child of the `BaseballFlightState` entity. while aerodynamics are
+/// active its rotation is set to the simulated seam orientation, so the rendered seams are the
+/// ones the aerodynamics used. the mesh's seams must line up with the `SeamGeometry` curve
+/// (swapped into bevy coord), and the child keeps its last rotation once aerodynamics stop
+#[derive(Debug, Clone, Copy, Default, Component, Reflect)]
+pub struct BaseballVisual;
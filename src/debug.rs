@@ -0,0 +1,224 @@
+use crate::*;
+use std::collections::VecDeque;
+
+/// draws the flight of every ball with gizmos: the trail, velocity, each force, the spin axis
+/// and the seams with the ones feeding ssw highlighted. what's drawn is set by `FlightDebugGizmos`
+pub struct BaseballFlightDebugPlugin;
+
+impl Plugin for BaseballFlightDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlightDebugGizmos>();
+        app.register_type::<FlightDebugGizmos>();
+
+        app.add_systems(
+            PostUpdate,
+            (record_flight_trails, draw_flight_gizmos)
+                .chain()
+                .run_if(|debug: Res<FlightDebugGizmos>| debug.enabled),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, Resource, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlightDebugGizmos {
+    pub enabled: bool,
+    pub trail: bool,
+    pub velocity: bool,
+    pub forces: bool,
+    pub spin_axis: bool,
+    pub seams: bool,
+    // points kept per trail
+    pub trail_length: usize,
+    // arrow length per m/s, in s
+    pub velocity_scale: f32,
+    // arrow length per m/s^2, in s^2
+    pub force_scale: f32,
+}
+
+impl Default for FlightDebugGizmos {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            trail: true,
+            velocity: true,
+            forces: true,
+            spin_axis: true,
+            seams: true,
+            trail_length: 1000,
+            velocity_scale: 0.02,
+            force_scale: 0.05,
+        }
+    }
+}
+
+/// where the ball has been this flight, in bevy coord. added by `BaseballFlightDebugPlugin`
+#[derive(Debug, Clone, Default, Component)]
+pub struct FlightTrail {
+    pub points: VecDeque<Vec3>,
+    // flight time of the last point, a smaller one means the ball was thrown again
+    time_elapsed: f64,
+}
+
+impl FlightTrail {
+    fn record(&mut self, point: Vec3, time_elapsed: f64, max_length: usize) {
+        if time_elapsed < self.time_elapsed {
+            self.points.clear();
+        }
+        self.time_elapsed = time_elapsed;
+        self.points.push_back(point);
+        while self.points.len() > max_length {
+            self.points.pop_front();
+        }
+    }
+}
+
+const TRAIL_COLOR: Color = Color::srgb(1., 1., 1.);
+const VELOCITY_COLOR: Color = Color::srgb(0., 1., 1.);
+const DRAG_COLOR: Color = Color::srgb(1., 0.2, 0.2);
+const MAGNUS_COLOR: Color = Color::srgb(0.2, 1., 0.2);
+const SSW_COLOR: Color = Color::srgb(1., 0.6, 0.);
+const KNUCKLEBALL_COLOR: Color = Color::srgb(1., 0., 1.);
+const GRAVITY_COLOR: Color = Color::srgb(0.3, 0.3, 1.);
+const SPIN_AXIS_COLOR: Color = Color::srgb(1., 1., 0.);
+const SEAM_COLOR: Color = Color::srgb(0.8, 0., 0.);
+const ACTIVE_SEAM_COLOR: Color = Color::srgb(1., 1., 0.);
+
+fn record_flight_trails(
+    mut commands: Commands,
+    debug: Res<FlightDebugGizmos>,
+    mut query_baseball: Query<(
+        Entity,
+        &BaseballFlightState,
+        &Transform,
+        Option<&mut FlightTrail>,
+    )>,
+) {
+    for (entity, state, transform, trail) in &mut query_baseball {
+        if !state.active {
+            continue;
+        }
+        match trail {
+            Some(mut trail) => trail.record(
+                transform.translation,
+                state.time_elapsed,
+                debug.trail_length,
+            ),
+            None => {
+                let mut trail = FlightTrail::default();
+                trail.record(
+                    transform.translation,
+                    state.time_elapsed,
+                    debug.trail_length,
+                );
+                commands.entity(entity).insert(trail);
+            }
+        }
+    }
+}
+
+fn draw_flight_gizmos(
+    mut gizmos: Gizmos,
+    debug: Res<FlightDebugGizmos>,
+    baseball_plugin_config: Res<BaseballPluginConfig>,
+    query_baseball: Query<(&BaseballFlightState, &Transform, Option<&FlightTrail>)>,
+) {
+    for (state, transform, trail) in &query_baseball {
+        // the last trail stays up after the ball lands
+        if debug.trail {
+            if let Some(trail) = trail {
+                gizmos.linestrip(trail.points.iter().copied(), TRAIL_COLOR);
+            }
+        }
+        if !state.active {
+            continue;
+        }
+        let center = transform.translation;
+
+        if debug.velocity {
+            let velocity = state.v.from_baseball_coord_to_bevy().as_vec3();
+            gizmos.arrow(
+                center,
+                center + velocity * debug.velocity_scale,
+                VELOCITY_COLOR,
+            );
+        }
+
+        if debug.forces {
+            let forces = &state.force_breakdown;
+            for (force, color) in [
+                (forces.drag, DRAG_COLOR),
+                (forces.magnus, MAGNUS_COLOR),
+                (forces.ssw, SSW_COLOR),
+                (forces.knuckleball, KNUCKLEBALL_COLOR),
+                (forces.gravity, GRAVITY_COLOR),
+            ] {
+                if force != DVec3::ZERO {
+                    let force = force.from_baseball_coord_to_bevy().as_vec3();
+                    gizmos.arrow(center, center + force * debug.force_scale, color);
+                }
+            }
+        }
+
+        if debug.spin_axis {
+            let axis = swap_coordinates_dvec3(&state.spin)
+                .normalize_or_zero()
+                .as_vec3()
+                * RADIUS
+                * 3.;
+            gizmos.line(center - axis, center + axis, SPIN_AXIS_COLOR);
+        }
+
+        if debug.seams && !state.seams.is_empty() {
+            // seams are in inches on a ball of SEAM_DIAMETER
+            let seam_to_bevy = |point: DVec3| {
+                center
+                    + (swap_coordinates_dvec3(&state.seam_orientation.mul_vec3(point))
+                        * (RADIUS as f64 / (SEAM_DIAMETER / 2.)))
+                        .as_vec3()
+            };
+            gizmos.linestrip(
+                state
+                    .seams
+                    .iter()
+                    .chain(state.seams.first())
+                    .map(|point| seam_to_bevy(*point)),
+                SEAM_COLOR,
+            );
+            for i in state.active_ssw_seams(&baseball_plugin_config.ssw) {
+                gizmos.sphere(
+                    seam_to_bevy(state.seams[i]),
+                    Quat::IDENTITY,
+                    RADIUS * 0.08,
+                    ACTIVE_SEAM_COLOR,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trail_keeps_the_latest_points() {
+        let mut trail = FlightTrail::default();
+        for i in 0..5 {
+            trail.record(Vec3::X * i as f32, i as f64 * 0.1, 3);
+        }
+        assert_eq!(
+            trail.points.iter().copied().collect::<Vec<_>>(),
+            [Vec3::X * 2., Vec3::X * 3., Vec3::X * 4.]
+        );
+    }
+
+    #[test]
+    fn trail_restarts_when_the_ball_is_thrown_again() {
+        let mut trail = FlightTrail::default();
+        trail.record(Vec3::X, 0.5, 10);
+        trail.record(Vec3::Y, 0.6, 10);
+        trail.record(Vec3::Z, 0., 10);
+        assert_eq!(trail.points.iter().copied().collect::<Vec<_>>(), [Vec3::Z]);
+    }
+}
@@ -1,14 +1,66 @@
 use crate::*;
 
 #[derive(Debug, Clone, Copy, Event)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ActivateAerodynamicsEvent {
     pub entity: Entity,
-    pub seam_y_angle: f32,
-    pub seam_z_angle: f32,
+    pub seam_orientation: SeamOrientation,
+}
+
+impl ActivateAerodynamicsEvent {
+    /// seams in the umba calculator's two angle form, in rad
+    pub fn from_angles(entity: Entity, seam_y_angle: f32, seam_z_angle: f32) -> Self {
+        Self {
+            entity,
+            seam_orientation: SeamOrientation::Angles {
+                y: seam_y_angle,
+                z: seam_z_angle,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Event)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PostActivateAerodynamicsEvent(pub Entity);
 
 #[derive(Debug, Clone, Copy, Event)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DisableAerodynamicsEvent(pub Entity);
+
+/// where a pitch passed the front of home plate
+#[derive(Debug, Clone, Copy, Event)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlateCrossingEvent {
+    pub entity: Entity,
+    // bevy coord
+    pub translation: Vec3,
+    pub velocity: Vec3,
+    // (horizontal, height) relative to the plate's center, in m, as the catcher sees it
+    pub location: Vec2,
+    // flight time to the plate, in s
+    pub time_elapsed: f64,
+    pub in_strike_zone: bool,
+}
+
+/// sent when a ball's aerodynamics had to be stopped
+#[derive(Debug, Clone, Copy, PartialEq, Event)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlightErrorEvent {
+    pub entity: Entity,
+    pub error: FlightError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FlightError {
+    /// the flight state went NaN or infinite, aerodynamics are disabled
+    NonFinite,
+}
+
+#[derive(Debug, Clone, Event)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RestoreBaseballFlightEvent {
+    pub entity: Entity,
+    pub snapshot: BaseballFlightSnapshot,
+}
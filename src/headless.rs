@@ -0,0 +1,423 @@
+use crate::*;
+use std::{fmt, thread};
+
+/// a pitch to fly without a bevy app, in the baseball coord and imperial units
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PitchSpec {
+    // release point, in ft
+    pub translation: DVec3,
+    // in ft/s
+    pub velocity: DVec3,
+    // in rad/s
+    pub spin: DVec3,
+    // `SeamOrientation::FromTransform` takes the seams as they are in `SeamGeometry`
+    pub seam_orientation: SeamOrientation,
+    pub knuckleball_seed: u64,
+    // how long to fly, in s
+    pub duration: f64,
+    // time between trajectory samples, in s
+    pub sample_interval: f64,
+}
+
+impl Default for PitchSpec {
+    /// 90 mph four seamer with 2200 rpm of backspin
+    fn default() -> Self {
+        Self {
+            translation: DVec3::new(0., 54., 6.),
+            velocity: DVec3::new(0., -90. * MPH_TO_FTS as f64, 0.),
+            spin: DVec3::new(-2200. * RPM_TO_RADS as f64, 0., 0.),
+            seam_orientation: SeamOrientation::Named(NamedSeamOrientation::FourSeam),
+            knuckleball_seed: 0,
+            duration: 0.5,
+            sample_interval: 0.01,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PitchSpecError {
+    NonFinite(&'static str),
+    Negative(&'static str),
+    NonPositive(&'static str),
+}
+
+impl fmt::Display for PitchSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFinite(field) => write!(f, "pitch `{field}` is not finite"),
+            Self::Negative(field) => write!(f, "pitch `{field}` is negative"),
+            Self::NonPositive(field) => write!(f, "pitch `{field}` has to be positive"),
+        }
+    }
+}
+
+impl std::error::Error for PitchSpecError {}
+
+/// why a pitch couldn't be simulated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimulateError {
+    Pitch(PitchSpecError),
+    Integrator(IntegratorError),
+}
+
+impl fmt::Display for SimulateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pitch(error) => error.fmt(f),
+            Self::Integrator(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SimulateError {}
+
+impl From<PitchSpecError> for SimulateError {
+    fn from(error: PitchSpecError) -> Self {
+        Self::Pitch(error)
+    }
+}
+
+impl From<IntegratorError> for SimulateError {
+    fn from(error: IntegratorError) -> Self {
+        Self::Integrator(error)
+    }
+}
+
+impl PitchSpec {
+    /// zero spin and a ball at rest are fine, they fly without the forces that need them
+    pub fn validate(&self) -> Result<(), PitchSpecError> {
+        let finite = |field, finite: bool| {
+            if finite {
+                Ok(())
+            } else {
+                Err(PitchSpecError::NonFinite(field))
+            }
+        };
+        finite("translation", self.translation.is_finite())?;
+        finite("velocity", self.velocity.is_finite())?;
+        finite("spin", self.spin.is_finite())?;
+        finite(
+            "seam_orientation",
+            match self.seam_orientation {
+                SeamOrientation::Angles { y, z } => y.is_finite() && z.is_finite(),
+                SeamOrientation::Quat(quat) => quat.is_finite() && quat.length_squared() > 0.,
+                SeamOrientation::FromTransform | SeamOrientation::Named(_) => true,
+            },
+        )?;
+        finite("duration", self.duration.is_finite())?;
+        finite("sample_interval", self.sample_interval.is_finite())?;
+        if self.duration < 0. {
+            return Err(PitchSpecError::Negative("duration"));
+        }
+        if self.sample_interval <= 0. {
+            return Err(PitchSpecError::NonPositive("sample_interval"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrajectorySample {
+    // in s
+    pub time: f64,
+    pub translation: DVec3,
+    pub velocity: DVec3,
+    pub spin: DVec3,
+}
+
+impl TrajectorySample {
+    // at the time the state has actually flown to, whole steps only
+    fn from_state(state: &BaseballFlightState) -> Self {
+        Self {
+            time: state.time_elapsed,
+            translation: state.translation,
+            velocity: state.v,
+            spin: state.spin,
+        }
+    }
+}
+
+/// samples of a simulated flight, the release included
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Trajectory {
+    pub samples: Vec<TrajectorySample>,
+}
+
+/// flies a single pitch with the config's model, seam geometry and integrator
+pub fn simulate(
+    config: &BaseballPluginConfig,
+    spec: &PitchSpec,
+) -> Result<Trajectory, SimulateError> {
+    config.integrator.validate()?;
+    spec.validate()?;
+
+    let mut state = BaseballFlightState::from_params(
+        spec.translation,
+        spec.velocity,
+        spec.spin,
+        spec.seam_orientation.to_quat(spec.spin, Quat::IDENTITY),
+        &config.seam_geometry,
+    );
+    state.knuckleball_flutter =
+        KnuckleballFlutter::from_seed(&config.knuckleball, spec.knuckleball_seed);
+    let model = config.model.get();
+
+    let sample_interval = spec.sample_interval.max(config.integrator.min_step());
+    let n_samples = (spec.duration / sample_interval).ceil().max(0.) as usize;
+    let mut samples = Vec::with_capacity(n_samples + 1);
+    samples.push(TrajectorySample::from_state(&state));
+
+    // the state carries partial steps over, so feeding it the time between samples keeps
+    // every sample on the same step grid. a sample can be up to a step short of its time
+    let mut time = 0.;
+    for i in 1..=n_samples {
+        let next_time = (i as f64 * sample_interval).min(spec.duration);
+        state.update_state(config, model, next_time - time);
+        time = next_time;
+        samples.push(TrajectorySample::from_state(&state));
+    }
+
+    Ok(Trajectory { samples })
+}
+
+/// flies every pitch, split evenly over all cores. results are in the order of `specs`
+pub fn simulate_many(
+    config: &BaseballPluginConfig,
+    specs: &[PitchSpec],
+) -> Vec<Result<Trajectory, SimulateError>> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = specs.len().div_ceil(threads).max(1);
+
+    thread::scope(|scope| {
+        let handles = specs
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|spec| simulate(config, spec))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adaptive_integrator_lands_on_rk4() {
+        // ssw switches seams in and out, which neither integrator resolves smoothly
+        let rk4 = BaseballPluginConfig {
+            ssw_on: false,
+            ..default()
+        };
+        let dormand_prince = BaseballPluginConfig {
+            integrator: Integrator::dormand_prince(),
+            ..rk4.clone()
+        };
+        let spec = PitchSpec::default();
+
+        let fixed = simulate(&rk4, &spec).unwrap();
+        let adaptive = simulate(&dormand_prince, &spec).unwrap();
+
+        assert_eq!(fixed.samples.len(), adaptive.samples.len());
+        let deviation = fixed
+            .samples
+            .iter()
+            .zip(&adaptive.samples)
+            .map(|(a, b)| a.translation.distance(b.translation))
+            .fold(0., f64::max);
+        // in ft, 0.01 in
+        assert!(
+            deviation < 0.01 / 12.,
+            "max deviation {} in",
+            deviation * 12.
+        );
+    }
+
+    #[test]
+    fn invalid_integrators_are_rejected() {
+        assert!(Integrator::default().validate().is_ok());
+        assert!(Integrator::dormand_prince().validate().is_ok());
+
+        for step in [0., -0.001, f64::NAN, f64::INFINITY] {
+            assert!(Integrator::Rk4 { step }.validate().is_err(), "step {step}");
+        }
+        let dormand_prince = |relative_tolerance, absolute_tolerance, min_step, max_step| {
+            Integrator::DormandPrince {
+                relative_tolerance,
+                absolute_tolerance,
+                min_step,
+                max_step,
+            }
+            .validate()
+        };
+        assert!(matches!(
+            dormand_prince(1e-6, 1e-6, 0., 0.01),
+            Err(IntegratorError::NonPositiveStep(_))
+        ));
+        assert!(matches!(
+            dormand_prince(1e-6, 1e-6, 0.01, 0.001),
+            Err(IntegratorError::StepRange { .. })
+        ));
+        assert_eq!(
+            dormand_prince(-1e-6, 1e-6, 1e-5, 0.01),
+            Err(IntegratorError::NegativeTolerance)
+        );
+        assert_eq!(
+            dormand_prince(f64::NAN, 1e-6, 1e-5, 0.01),
+            Err(IntegratorError::NegativeTolerance)
+        );
+        assert_eq!(
+            dormand_prince(0., 0., 1e-5, 0.01),
+            Err(IntegratorError::ZeroTolerance)
+        );
+
+        let config = BaseballPluginConfig {
+            integrator: Integrator::Rk4 { step: 0. },
+            ..default()
+        };
+        assert_eq!(
+            simulate(&config, &PitchSpec::default()).unwrap_err(),
+            SimulateError::Integrator(IntegratorError::NonPositiveStep(0.))
+        );
+        assert!(simulate_many(&config, &[PitchSpec::default()])[0].is_err());
+    }
+
+    #[test]
+    fn simulate_many_matches_simulate_in_order() {
+        let config = BaseballPluginConfig::default();
+        let mut specs = (0..9)
+            .map(|i| PitchSpec {
+                spin: PitchSpec::default().spin + DVec3::Z * i as f64 * 10.,
+                ..default()
+            })
+            .collect::<Vec<_>>();
+        specs[4].duration = -1.;
+
+        let trajectories = simulate_many(&config, &specs);
+        assert_eq!(trajectories.len(), specs.len());
+        for (spec, trajectory) in specs.iter().zip(&trajectories) {
+            match (simulate(&config, spec), trajectory) {
+                (Ok(expected), Ok(trajectory)) => {
+                    assert_eq!(expected.samples.len(), trajectory.samples.len());
+                    for (a, b) in expected.samples.iter().zip(&trajectory.samples) {
+                        assert_eq!(a.time, b.time);
+                        assert_eq!(a.translation, b.translation);
+                    }
+                }
+                (Err(expected), Err(error)) => assert_eq!(expected, *error),
+                (expected, trajectory) => panic!("{expected:?} vs {trajectory:?}"),
+            }
+        }
+        assert!(trajectories[4].is_err());
+    }
+
+    #[test]
+    fn samples_are_labelled_with_the_flown_time() {
+        let spec = PitchSpec {
+            // not a whole number of 1 ms steps
+            sample_interval: 0.0125,
+            ..default()
+        };
+        let trajectory = simulate(&BaseballPluginConfig::default(), &spec).unwrap();
+        for (i, sample) in trajectory.samples.iter().enumerate() {
+            let requested = (i as f64 * spec.sample_interval).min(spec.duration);
+            assert!(sample.time <= requested + 1e-9);
+            assert!(requested - sample.time < 0.001);
+            // whole steps
+            assert!((sample.time * 1000. - (sample.time * 1000.).round()).abs() < 1e-6);
+        }
+    }
+
+    fn assert_finite(trajectory: &Trajectory) {
+        assert!(trajectory
+            .samples
+            .iter()
+            .all(|sample| sample.translation.is_finite()
+                && sample.velocity.is_finite()
+                && sample.spin.is_finite()));
+    }
+
+    #[test]
+    fn pitch_without_spin_or_speed_flies() {
+        let config = BaseballPluginConfig::default();
+        for spec in [
+            PitchSpec {
+                spin: DVec3::ZERO,
+                ..default()
+            },
+            PitchSpec {
+                velocity: DVec3::ZERO,
+                ..default()
+            },
+        ] {
+            assert_eq!(spec.validate(), Ok(()));
+            assert_finite(&simulate(&config, &spec).unwrap());
+        }
+    }
+
+    #[test]
+    fn invalid_pitches_are_rejected() {
+        let nan = DVec3::splat(f64::NAN);
+        let invalid = |spec: PitchSpec| spec.validate().unwrap_err();
+        assert_eq!(
+            invalid(PitchSpec {
+                translation: nan,
+                ..default()
+            }),
+            PitchSpecError::NonFinite("translation")
+        );
+        assert_eq!(
+            invalid(PitchSpec {
+                velocity: nan,
+                ..default()
+            }),
+            PitchSpecError::NonFinite("velocity")
+        );
+        assert_eq!(
+            invalid(PitchSpec {
+                spin: nan,
+                ..default()
+            }),
+            PitchSpecError::NonFinite("spin")
+        );
+        assert_eq!(
+            invalid(PitchSpec {
+                duration: -1.,
+                ..default()
+            }),
+            PitchSpecError::Negative("duration")
+        );
+        assert_eq!(
+            invalid(PitchSpec {
+                sample_interval: 0.,
+                ..default()
+            }),
+            PitchSpecError::NonPositive("sample_interval")
+        );
+        assert!(simulate(
+            &BaseballPluginConfig::default(),
+            &PitchSpec {
+                spin: nan,
+                ..default()
+            }
+        )
+        .is_err());
+    }
+}
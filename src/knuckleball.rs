@@ -0,0 +1,117 @@
+use crate::*;
+
+/// unsteady seam forces on (nearly) spinless pitches. seams sitting near the separation line
+/// trip the boundary layer on their side, pushing the ball towards them, and the wake sheds
+/// unsteadily on top of that, so the force wanders over the flight
+#[derive(Debug, Copy, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Knuckleball {
+    pub enabled: bool,
+    // balls spinning slower than this are knuckleballs, in rad/s
+    pub spin_threshold: f64,
+    // force coefficient of a seam right on the separation line
+    pub seam_coefficient: f64,
+    // angle from the stagnation point where the flow separates, in rad
+    pub separation_angle: f64,
+    // how far from the separation line a seam still trips the flow, in rad
+    pub separation_width: f64,
+    // force coefficient of the unsteady wake
+    pub flutter_coefficient: f64,
+    // in Hz, every ball gets its own frequencies around this
+    pub flutter_frequency: f64,
+    // mixed with the entity of balls without a `KnuckleballSeed`
+    pub seed: u64,
+}
+
+impl Default for Knuckleball {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            // ~100 rpm
+            spin_threshold: 100. * RPM_TO_RADS as f64,
+            seam_coefficient: 0.1,
+            separation_angle: 100. * PI_64 / 180.,
+            separation_width: 10. * PI_64 / 180.,
+            flutter_coefficient: 0.02,
+            flutter_frequency: 2.,
+            seed: 0,
+        }
+    }
+}
+
+/// seed for the wake flutter of a single ball, so the same knuckleball can be thrown again
+#[derive(Debug, Copy, Clone, Component, Reflect)]
+pub struct KnuckleballSeed(pub u64);
+
+/// per ball wake flutter, drawn from the seed at activation
+#[derive(Debug, Copy, Clone, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KnuckleballFlutter {
+    // in Hz
+    pub frequencies: [f64; 2],
+    // in rad
+    pub phases: [f64; 2],
+}
+
+impl KnuckleballFlutter {
+    pub(crate) fn from_seed(knuckleball: &Knuckleball, seed: u64) -> Self {
+        let mut rng = seed;
+        let mut next = || split_mix_64(&mut rng) as f64 / u64::MAX as f64;
+        Self {
+            frequencies: [
+                knuckleball.flutter_frequency * (0.5 + next()),
+                knuckleball.flutter_frequency * (0.5 + next()),
+            ],
+            phases: [2. * PI_64 * next(), 2. * PI_64 * next()],
+        }
+    }
+}
+
+impl Knuckleball {
+    /// acceleration per (c_0 * v^2). `seams` are in the body frame, `orientation` places them
+    pub(crate) fn force(
+        &self,
+        flutter: &KnuckleballFlutter,
+        direction: DVec3,
+        seams: &[DVec3],
+        seam_weights: &[f64],
+        orientation: DQuat,
+        time_elapsed: f64,
+    ) -> DVec3 {
+        // seam position force map, weighted by the seam length each point stands for and divided
+        // by the weights' sum, N_SEAMS, so it doesn't depend on how many seam points there are.
+        // worked out in the body frame and rotated out once
+        let seam_force = orientation.mul_vec3(self.seam_force(
+            orientation.inverse().mul_vec3(direction),
+            seams,
+            seam_weights,
+        ));
+
+        // wake axes fixed to the flow, horizontal and the one across it, so the flutter doesn't
+        // jump as the flow direction changes
+        let e_1 = DVec3::Z
+            .cross(direction)
+            .try_normalize()
+            .unwrap_or(DVec3::X);
+        let e_2 = direction.cross(e_1);
+        let wake_force = e_1
+            * (2. * PI_64 * flutter.frequencies[0] * time_elapsed + flutter.phases[0]).sin()
+            + e_2 * (2. * PI_64 * flutter.frequencies[1] * time_elapsed + flutter.phases[1]).sin();
+
+        seam_force * self.seam_coefficient + wake_force * self.flutter_coefficient
+    }
+
+    fn seam_force(&self, direction: DVec3, seams: &[DVec3], seam_weights: &[f64]) -> DVec3 {
+        seams
+            .iter()
+            .zip(seam_weights)
+            .fold(DVec3::ZERO, |force, (point, seam_weight)| {
+                let point = point.normalize_or_zero();
+                let angle = (-direction).dot(point).clamp(-1., 1.).acos();
+                let weight =
+                    (-((angle - self.separation_angle) / self.separation_width).powi(2)).exp();
+                force + (point - direction * direction.dot(point)) * weight * *seam_weight
+            })
+            / N_SEAMS as f64
+    }
+}
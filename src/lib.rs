@@ -1,72 +1,279 @@
+mod aerodynamic_table;
+mod aerodynamics;
 mod ball_flight_state;
 mod common;
 mod components;
+#[cfg(feature = "debug-gizmos")]
+mod debug;
 mod events;
+mod headless;
+mod knuckleball;
+mod lod;
+mod plate;
+#[cfg(feature = "debug-gizmos")]
+mod plate_overlay;
 mod resources;
+mod seam_geometry;
+mod seam_orientation;
+mod snapshot;
+mod spin_axis;
 mod systems;
 
 pub mod prelude {
     pub use super::{
-        components::*, constants::*, events::*, utils::*, BaseballFlightPlugin, GyroPole, Tilt,
+        aerodynamic_table::*,
+        aerodynamics::*,
+        ball_flight_state::{BaseballFlightState, ForceBreakdown},
+        components::*,
+        constants::*,
+        events::*,
+        headless::*,
+        knuckleball::*,
+        lod::*,
+        plate::*,
+        resources::{
+            Atmosphere, BaseballPluginConfig, FlightIntegrationMode, Integrator, IntegratorError,
+            SeamShiftedWake, SpinDecay,
+        },
+        seam_geometry::*,
+        seam_orientation::*,
+        snapshot::*,
+        spin_axis::*,
+        utils::*,
+        BaseballFlightPlugin, BaseballFlightSet, GyroPole, Tilt,
     };
+
+    #[cfg(feature = "debug-gizmos")]
+    pub use super::debug::*;
+    #[cfg(feature = "debug-gizmos")]
+    pub use super::plate_overlay::*;
 }
 
 use crate::systems::*;
 
 pub(crate) use crate::resources::*;
+pub(crate) use aerodynamics::*;
+pub(crate) use avian3d::dynamics::integrator::IntegrationSet;
 pub(crate) use avian3d::prelude::*;
 pub(crate) use ball_flight_state::*;
-pub(crate) use bevy::{math::*, prelude::*};
+pub(crate) use bevy::{
+    ecs::schedule::{InternedScheduleLabel, ScheduleLabel},
+    math::*,
+    prelude::*,
+};
 pub(crate) use common::*;
+pub(crate) use components::*;
 pub(crate) use constants::*;
 pub(crate) use events::*;
+pub(crate) use knuckleball::*;
+pub(crate) use lod::*;
+pub(crate) use plate::*;
+pub(crate) use seam_geometry::*;
+pub(crate) use seam_orientation::*;
+pub(crate) use snapshot::*;
+pub(crate) use spin_axis::*;
 pub(crate) use utils::*;
 
+#[cfg(feature = "serde")]
+pub(crate) use serde::{Deserialize, Serialize};
+
 pub struct BaseballFlightPlugin {
     pub ssw_on: bool,
     pub magnus_on: bool,
     pub drag_on: bool,
+    /// lift/drag/ssw coefficient model, overridable per ball with an `AerodynamicModelHandle` component
+    pub model: AerodynamicModelHandle,
+    /// how the simulated flight is handed to the ball entity
+    pub integration_mode: FlightIntegrationMode,
+    /// evaluate the aerodynamic forces inside avian's `SubstepSchedule` instead of once per fixed step.
+    /// only applies to `FlightIntegrationMode::ForceDriven`
+    pub substep_aerodynamics: bool,
+    /// how the flight is stepped. the plugin panics when it's invalid, see `Integrator::validate`
+    pub integrator: Integrator,
+    /// schedule the flight systems run in. it has to run before avian's, which is `PostUpdate`
+    /// by default. in avian's own schedule the flight is ordered before `PhysicsSet::StepSimulation`
+    pub schedule: InternedScheduleLabel,
+}
+
+impl BaseballFlightPlugin {
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            ..default()
+        }
+    }
+}
+
+impl Default for BaseballFlightPlugin {
+    fn default() -> Self {
+        Self {
+            ssw_on: true,
+            magnus_on: true,
+            drag_on: true,
+            model: AerodynamicModelHandle::default(),
+            integration_mode: FlightIntegrationMode::default(),
+            substep_aerodynamics: false,
+            integrator: Integrator::default(),
+            schedule: FixedUpdate.intern(),
+        }
+    }
 }
 
 impl Plugin for BaseballFlightPlugin {
     fn build(&self, app: &mut App) {
+        if let Err(error) = self.integrator.validate() {
+            panic!("invalid BaseballFlightPlugin::integrator: {error}");
+        }
+
         app.add_event::<ActivateAerodynamicsEvent>();
         app.add_event::<PostActivateAerodynamicsEvent>();
         app.add_event::<DisableAerodynamicsEvent>();
+        app.add_event::<RestoreBaseballFlightEvent>();
+        app.add_event::<FlightErrorEvent>();
+        app.add_event::<PlateCrossingEvent>();
 
         app.register_type::<BaseballFlightState>();
+        app.register_type::<BaseballVisual>();
+        app.register_type::<FlightLod>();
+        app.register_type::<FlightLodFocus>();
+        app.register_type::<FlightLodPolicy>();
+        app.register_type::<HomePlate>();
+        app.register_type::<StrikeZone>();
+        app.register_type::<PitchType>();
+
+        app.init_resource::<FlightLodPolicy>();
+        app.init_resource::<HomePlate>();
+        app.init_resource::<StrikeZone>();
 
         app.insert_resource(BaseballPluginConfig {
             ssw_on: self.ssw_on,
             magnus_on: self.magnus_on,
             drag_on: self.drag_on,
+            integration_mode: self.integration_mode,
+            integrator: self.integrator,
+            model: self.model.clone(),
             ..default()
         });
 
-        // app.add_systems(FixedUpdate, _apply_physics_option_1);
-        // app.add_systems(FixedUpdate, _apply_physics_option_2);
-        app.add_systems(FixedUpdate, _apply_physics_option_3);
+        app.configure_sets(
+            self.schedule,
+            (
+                BaseballFlightSet::Activate,
+                BaseballFlightSet::Simulate,
+                BaseballFlightSet::Deactivate,
+                BaseballFlightSet::Metrics,
+            )
+                .chain(),
+        );
+        // only orders anything when the flight shares avian's schedule
+        app.configure_sets(
+            self.schedule,
+            (BaseballFlightSet::Activate, BaseballFlightSet::Simulate)
+                .before(PhysicsSet::StepSimulation),
+        );
 
-        app.add_systems(FixedUpdate, activate_aerodynamics);
-        app.add_systems(FixedUpdate, disable_aerodynamics);
+        app.add_systems(
+            self.schedule,
+            (
+                (activate_aerodynamics, restore_baseball_flight),
+                apply_flight_lod,
+            )
+                .chain()
+                .in_set(BaseballFlightSet::Activate),
+        );
+
+        match self.integration_mode {
+            FlightIntegrationMode::Kinematic => {
+                app.add_systems(
+                    self.schedule,
+                    apply_kinematic.in_set(BaseballFlightSet::Simulate),
+                );
+            }
+            FlightIntegrationMode::VelocityDriven => {
+                app.configure_sets(
+                    PhysicsSchedule,
+                    BaseballFlightSet::Simulate.in_set(PhysicsStepSet::First),
+                );
+                app.add_systems(
+                    PhysicsSchedule,
+                    apply_velocity_driven.in_set(BaseballFlightSet::Simulate),
+                );
+            }
+            FlightIntegrationMode::ForceDriven if self.substep_aerodynamics => {
+                app.configure_sets(
+                    SubstepSchedule,
+                    BaseballFlightSet::Simulate.before(IntegrationSet::Velocity),
+                );
+                app.add_systems(
+                    SubstepSchedule,
+                    apply_force_driven_substep.in_set(BaseballFlightSet::Simulate),
+                );
+            }
+            FlightIntegrationMode::ForceDriven => {
+                app.configure_sets(
+                    PhysicsSchedule,
+                    BaseballFlightSet::Simulate.in_set(PhysicsStepSet::First),
+                );
+                app.add_systems(
+                    PhysicsSchedule,
+                    apply_force_driven.in_set(BaseballFlightSet::Simulate),
+                );
+            }
+            FlightIntegrationMode::Internal => {
+                app.add_systems(
+                    self.schedule,
+                    apply_internal.in_set(BaseballFlightSet::Simulate),
+                );
+            }
+        }
+
+        app.add_systems(
+            self.schedule,
+            (check_flight_finite, disable_aerodynamics)
+                .chain()
+                .in_set(BaseballFlightSet::Deactivate),
+        );
+
+        app.add_systems(
+            self.schedule,
+            detect_plate_crossings.in_set(BaseballFlightSet::Metrics),
+        );
+
+        app.add_systems(
+            PostUpdate,
+            sync_seam_visuals.before(TransformSystem::TransformPropagate),
+        );
     }
 }
 
-#[derive(Debug, Clone, Copy, Reflect)]
+/// system sets of the flight plugin, chained in this order in the plugin's schedule.
+/// in the velocity and force driven modes `Simulate` runs at the start of every step of avian's
+/// `PhysicsSchedule` instead, so the flight follows avian's timestep. when `substep_aerodynamics`
+/// is on, it runs in avian's `SubstepSchedule`
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BaseballFlightSet {
+    /// activation and snapshot restores
+    Activate,
+    /// aerodynamics
+    Simulate,
+    Deactivate,
+    /// for anything that reads the flight state after the step
+    Metrics,
+}
+
+#[derive(Debug, Clone, Copy, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GyroPole {
+    #[default]
     Right,
     Left,
 }
 
-impl Default for GyroPole {
-    fn default() -> Self {
-        Self::Right
-    }
-}
-
 #[derive(Debug, Reflect, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Tilt(f32);
 impl Tilt {
+    #[deprecated(note = "panics on an out of range hour, use `try_from_hour_minutes`")]
     pub fn from_hour_mintes(h: i8, m: i8) -> Self {
         assert!(h <= 12 && h > 0);
         let rad_hrs = (h - 3) as f32 * PI_32 / 6.;
@@ -74,7 +281,148 @@ impl Tilt {
         Self(rad_hrs + rad_mins)
     }
 
+    /// hour within 1..=12, minute within 0..=59
+    pub fn try_from_hour_minutes(h: i8, m: i8) -> Result<Self, SpinAxisError> {
+        if !(1..=12).contains(&h) {
+            return Err(SpinAxisError::HourOutOfRange(h));
+        }
+        if !(0..=59).contains(&m) {
+            return Err(SpinAxisError::MinuteOutOfRange(m));
+        }
+        let rad_hrs = (h - 3) as f32 * PI_32 / 6.;
+        let rad_mins = m as f32 * PI_32 / 360.;
+        Ok(Self(rad_hrs + rad_mins))
+    }
+
     pub fn get(&self) -> f32 {
         self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tilt_rejects_out_of_range_clock() {
+        assert!(Tilt::try_from_hour_minutes(12, 0).is_ok());
+        assert!(Tilt::try_from_hour_minutes(1, 59).is_ok());
+        assert_eq!(
+            Tilt::try_from_hour_minutes(0, 0).unwrap_err(),
+            SpinAxisError::HourOutOfRange(0)
+        );
+        assert_eq!(
+            Tilt::try_from_hour_minutes(13, 0).unwrap_err(),
+            SpinAxisError::HourOutOfRange(13)
+        );
+        assert_eq!(
+            Tilt::try_from_hour_minutes(1, 60).unwrap_err(),
+            SpinAxisError::MinuteOutOfRange(60)
+        );
+        assert_eq!(
+            Tilt::try_from_hour_minutes(1, -1).unwrap_err(),
+            SpinAxisError::MinuteOutOfRange(-1)
+        );
+    }
+
+    // labels of the systems below, in the order they ran
+    #[derive(Debug, Default, Resource)]
+    struct Ran(Vec<&'static str>);
+
+    fn ran(label: &'static str) -> impl FnMut(ResMut<Ran>) {
+        move |mut ran: ResMut<Ran>| ran.0.push(label)
+    }
+
+    // the first update that steps avian, with the plugin sharing avian's `PostUpdate`. there's a
+    // system in each flight set and one at the end of avian's step
+    fn first_step(plugin: BaseballFlightPlugin) -> Vec<&'static str> {
+        use std::time::Duration;
+
+        let plugin = BaseballFlightPlugin {
+            schedule: PostUpdate.intern(),
+            ..plugin
+        };
+        let simulate = match plugin.integration_mode {
+            FlightIntegrationMode::ForceDriven if plugin.substep_aerodynamics => {
+                SubstepSchedule.intern()
+            }
+            FlightIntegrationMode::VelocityDriven | FlightIntegrationMode::ForceDriven => {
+                PhysicsSchedule.intern()
+            }
+            FlightIntegrationMode::Kinematic | FlightIntegrationMode::Internal => plugin.schedule,
+        };
+        let schedule = plugin.schedule;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin));
+        // avian's scene support, on with `render`, needs the scene spawner
+        #[cfg(feature = "render")]
+        app.add_plugins((AssetPlugin::default(), bevy::scene::ScenePlugin));
+        app.add_plugins((PhysicsPlugins::default(), plugin));
+        app.insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(
+            Duration::from_secs_f64(1. / 60.),
+        ));
+        app.init_resource::<Ran>();
+        app.add_systems(
+            schedule,
+            (
+                ran("activate").in_set(BaseballFlightSet::Activate),
+                ran("deactivate").in_set(BaseballFlightSet::Deactivate),
+                ran("metrics").in_set(BaseballFlightSet::Metrics),
+            ),
+        );
+        app.add_systems(
+            simulate,
+            ran("simulate").in_set(BaseballFlightSet::Simulate),
+        );
+        app.add_systems(PhysicsSchedule, ran("step").in_set(PhysicsStepSet::Last));
+
+        loop {
+            app.update();
+            let ran = std::mem::take(&mut app.world_mut().resource_mut::<Ran>().0);
+            if ran.contains(&"step") {
+                return ran;
+            }
+        }
+    }
+
+    fn assert_before(ran: &[&str], first: &str, second: &str) {
+        let position = |label| ran.iter().position(|ran| *ran == label).unwrap();
+        assert!(position(first) < position(second), "{ran:?}");
+    }
+
+    #[test]
+    fn flight_sets_run_before_avian_in_its_own_schedule() {
+        let ran = first_step(BaseballFlightPlugin {
+            integration_mode: FlightIntegrationMode::Kinematic,
+            ..default()
+        });
+        for (first, second) in [
+            ("activate", "simulate"),
+            ("simulate", "deactivate"),
+            ("deactivate", "metrics"),
+            ("simulate", "step"),
+        ] {
+            assert_before(&ran, first, second);
+        }
+    }
+
+    #[test]
+    fn avian_driven_flight_simulates_inside_the_physics_step() {
+        for plugin in [
+            BaseballFlightPlugin::default(),
+            BaseballFlightPlugin {
+                integration_mode: FlightIntegrationMode::VelocityDriven,
+                ..default()
+            },
+            BaseballFlightPlugin {
+                substep_aerodynamics: true,
+                ..default()
+            },
+        ] {
+            let ran = first_step(plugin);
+            assert_before(&ran, "activate", "simulate");
+            assert_before(&ran, "simulate", "step");
+        }
+    }
+}
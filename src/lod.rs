@@ -0,0 +1,175 @@
+use crate::*;
+
+/// how much of the aerodynamics a ball is flown with.
+/// as a component it pins the ball's level, otherwise `FlightLodPolicy` picks it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FlightLod {
+    /// rk4 with seam shifted wake and knuckleball forces
+    #[default]
+    Full,
+    /// rk4 with magnus and drag only, no seam forces
+    MagnusDrag,
+    /// the acceleration is taken once per update and held constant over it
+    Analytic,
+}
+
+/// distances are measured from the closest of these, usually the camera
+#[derive(Debug, Clone, Copy, Default, Component, Reflect)]
+pub struct FlightLodFocus;
+
+/// picks the `FlightLod` of every active ball without a `FlightLod` component.
+/// the default flies everything `Full`
+#[derive(Debug, Clone, Copy, Resource, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlightLodPolicy {
+    // balls further than this from a `FlightLodFocus` drop to `MagnusDrag`, in m
+    pub magnus_drag_distance: f32,
+    // and further than this to `Analytic`, in m
+    pub analytic_distance: f32,
+    // at most this many balls fly `Full`, closest first. pinned balls count towards it
+    pub full_budget: Option<usize>,
+    // same for `MagnusDrag`, balls over both budgets fly `Analytic`
+    pub magnus_drag_budget: Option<usize>,
+}
+
+impl Default for FlightLodPolicy {
+    fn default() -> Self {
+        Self {
+            magnus_drag_distance: f32::INFINITY,
+            analytic_distance: f32::INFINITY,
+            full_budget: None,
+            magnus_drag_budget: None,
+        }
+    }
+}
+
+impl FlightLodPolicy {
+    pub(crate) fn lod_at(&self, distance: f32) -> FlightLod {
+        if distance > self.analytic_distance {
+            FlightLod::Analytic
+        } else if distance > self.magnus_drag_distance {
+            FlightLod::MagnusDrag
+        } else {
+            FlightLod::Full
+        }
+    }
+
+    /// fills in the level of every `(ball, distance, pinned)`. pinned balls go first so they use
+    /// up the budgets, then the rest closest to a focus first
+    pub(crate) fn assign(&self, balls: &mut [(Entity, f32, Option<FlightLod>)]) {
+        balls.sort_by(|(_, distance_a, pinned_a), (_, distance_b, pinned_b)| {
+            pinned_b
+                .is_some()
+                .cmp(&pinned_a.is_some())
+                .then(distance_a.total_cmp(distance_b))
+        });
+
+        let (mut n_full, mut n_magnus_drag) = (0, 0);
+        for (_, distance, lod) in balls {
+            let lod = lod.get_or_insert_with(|| {
+                let mut lod = self.lod_at(*distance);
+                if lod == FlightLod::Full && self.full_budget.is_some_and(|budget| n_full >= budget)
+                {
+                    lod = FlightLod::MagnusDrag;
+                }
+                if lod == FlightLod::MagnusDrag
+                    && self
+                        .magnus_drag_budget
+                        .is_some_and(|budget| n_magnus_drag >= budget)
+                {
+                    lod = FlightLod::Analytic;
+                }
+                lod
+            });
+            match lod {
+                FlightLod::Full => n_full += 1,
+                FlightLod::MagnusDrag => n_magnus_drag += 1,
+                FlightLod::Analytic => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assign(policy: FlightLodPolicy, balls: &[(f32, Option<FlightLod>)]) -> Vec<FlightLod> {
+        let mut assigned = balls
+            .iter()
+            .enumerate()
+            .map(|(i, &(distance, pinned))| (Entity::from_raw(i as u32), distance, pinned))
+            .collect::<Vec<_>>();
+        policy.assign(&mut assigned);
+        // back in the order the balls came in
+        assigned.sort_by_key(|(entity, _, _)| *entity);
+        assigned
+            .into_iter()
+            .map(|(_, _, lod)| lod.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn distance_picks_the_level() {
+        let policy = FlightLodPolicy {
+            magnus_drag_distance: 10.,
+            analytic_distance: 50.,
+            ..default()
+        };
+        assert_eq!(
+            assign(policy, &[(60., None), (5., None), (10., None), (30., None)]),
+            [
+                FlightLod::Analytic,
+                FlightLod::Full,
+                FlightLod::Full,
+                FlightLod::MagnusDrag,
+            ]
+        );
+        assert_eq!(
+            assign(FlightLodPolicy::default(), &[(1e6, None)]),
+            [FlightLod::Full]
+        );
+    }
+
+    #[test]
+    fn budgets_go_to_the_closest_balls_after_the_pinned_ones() {
+        let policy = FlightLodPolicy {
+            full_budget: Some(2),
+            magnus_drag_budget: Some(1),
+            ..default()
+        };
+        assert_eq!(
+            assign(
+                policy,
+                &[
+                    (40., None),
+                    (10., None),
+                    (30., None),
+                    (90., Some(FlightLod::Full)),
+                    (20., None),
+                ]
+            ),
+            [
+                FlightLod::Analytic,
+                FlightLod::Full,
+                FlightLod::Analytic,
+                FlightLod::Full,
+                FlightLod::MagnusDrag,
+            ]
+        );
+    }
+
+    #[test]
+    fn pinned_balls_keep_their_level_over_budget() {
+        let policy = FlightLodPolicy {
+            full_budget: Some(0),
+            magnus_drag_budget: Some(0),
+            ..default()
+        };
+        assert_eq!(
+            assign(policy, &[(0., Some(FlightLod::Full)), (0., None)]),
+            [FlightLod::Full, FlightLod::Analytic]
+        );
+    }
+}
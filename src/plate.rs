@@ -0,0 +1,220 @@
+use crate::*;
+
+// in m
+pub(crate) const PLATE_WIDTH: f32 = 17. * 0.0254;
+pub(crate) const PLATE_DEPTH: f32 = 17. * 0.0254;
+pub(crate) const PLATE_SIDE: f32 = 8.5 * 0.0254;
+
+/// home plate in bevy coord. pitches travel towards -z and are measured at the plate's front edge
+#[derive(Debug, Clone, Copy, Resource, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HomePlate {
+    // back tip of the plate, on the ground
+    pub translation: Vec3,
+}
+
+impl Default for HomePlate {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+        }
+    }
+}
+
+impl HomePlate {
+    /// z of the plane pitches are measured at
+    pub fn front(&self) -> f32 {
+        self.translation.z + PLATE_DEPTH
+    }
+
+    /// outline on the ground, front edge first
+    pub fn outline(&self) -> [Vec3; 5] {
+        let half_width = PLATE_WIDTH / 2.;
+        [
+            Vec3::new(-half_width, 0., PLATE_DEPTH),
+            Vec3::new(half_width, 0., PLATE_DEPTH),
+            Vec3::new(half_width, 0., PLATE_DEPTH - PLATE_SIDE),
+            Vec3::ZERO,
+            Vec3::new(-half_width, 0., PLATE_DEPTH - PLATE_SIDE),
+        ]
+        .map(|corner| self.translation + corner)
+    }
+}
+
+/// the current batter's strike zone, heights above the plate in m. the zone is as wide as the plate
+#[derive(Debug, Clone, Copy, Resource, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StrikeZone {
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl Default for StrikeZone {
+    fn default() -> Self {
+        // statcast's average of ~3.4 ft and ~1.6 ft
+        Self {
+            top: 1.04,
+            bottom: 0.49,
+        }
+    }
+}
+
+impl StrikeZone {
+    /// rule of thumb from the batter's height in m, midpoint of the shoulders and belt to the
+    /// hollow below the kneecap
+    pub fn from_batter_height(height: f32) -> Self {
+        Self {
+            top: 0.535 * height,
+            bottom: 0.27 * height,
+        }
+    }
+
+    /// `location` is (horizontal, height) relative to the plate's center, in m. any part of the
+    /// ball touching the zone counts
+    pub fn contains(&self, location: Vec2) -> bool {
+        location.x.abs() <= PLATE_WIDTH / 2. + RADIUS
+            && location.y >= self.bottom - RADIUS
+            && location.y <= self.top + RADIUS
+    }
+}
+
+// backs each ball that passed the plate this step up to the plane along its velocity
+pub(crate) fn detect_plate_crossings(
+    home_plate: Res<HomePlate>,
+    strike_zone: Res<StrikeZone>,
+    mut query_baseball: Query<(Entity, &mut BaseballFlightState)>,
+    mut ev_plate_crossing_event: EventWriter<PlateCrossingEvent>,
+) {
+    let front = home_plate.front();
+    for (entity, mut state) in &mut query_baseball {
+        if !state.active || state.crossed_plate {
+            continue;
+        }
+        let translation = state.translation.from_baseball_coord_to_bevy().as_vec3();
+        let velocity = state.v.from_baseball_coord_to_bevy().as_vec3();
+        if translation.z > front || velocity.z >= 0. {
+            continue;
+        }
+        state.crossed_plate = true;
+
+        let t_back = (front - translation.z) / velocity.z;
+        // released behind the plate, there is nothing to measure
+        if t_back < -state.time_elapsed as f32 {
+            continue;
+        }
+        let crossing = translation + velocity * t_back;
+        let relative = crossing - home_plate.translation;
+        // the catcher looks along +z, towards the pitcher, so their right is -x
+        let location = Vec2::new(-relative.x, relative.y);
+
+        ev_plate_crossing_event.send(PlateCrossingEvent {
+            entity,
+            translation: crossing,
+            velocity,
+            location,
+            time_elapsed: state.time_elapsed + t_back as f64,
+            in_strike_zone: strike_zone.contains(location),
+        });
+    }
+}
+
+/// what a ball was thrown as, read by the plate overlay. purely a label, the flight doesn't use it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Component, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PitchType {
+    #[default]
+    FourSeam,
+    TwoSeam,
+    Sinker,
+    Cutter,
+    Slider,
+    Sweeper,
+    Curveball,
+    Changeup,
+    Splitter,
+    Knuckleball,
+    Other,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // flies one update of `detect_plate_crossings` for a ball at `translation` with `velocity`,
+    // both bevy coord, half a second into its flight
+    fn crossings(translation: Vec3, velocity: Vec3) -> Vec<PlateCrossingEvent> {
+        let mut app = App::new();
+        app.add_event::<PlateCrossingEvent>();
+        app.init_resource::<HomePlate>();
+        app.init_resource::<StrikeZone>();
+        app.add_systems(Update, detect_plate_crossings);
+
+        app.world_mut().spawn(BaseballFlightState {
+            active: true,
+            time_elapsed: 0.5,
+            translation: translation.from_bevy_to_baseball_coord().as_dvec3(),
+            v: velocity.from_bevy_to_baseball_coord().as_dvec3(),
+            ..default()
+        });
+
+        app.update();
+        let events = app.world().resource::<Events<PlateCrossingEvent>>();
+        let first = events
+            .get_reader()
+            .read(events)
+            .copied()
+            .collect::<Vec<_>>();
+        // a ball is measured once
+        app.update();
+        app.update();
+        assert!(app
+            .world()
+            .resource::<Events<PlateCrossingEvent>>()
+            .is_empty());
+        first
+    }
+
+    #[test]
+    fn crossing_is_backed_up_to_the_front_of_the_plate() {
+        let front = HomePlate::default().front();
+        let crossing = crossings(Vec3::new(0.1, 0.7, front - 0.1), Vec3::new(0., -1., -40.));
+        assert_eq!(crossing.len(), 1);
+        let crossing = crossing[0];
+
+        // 0.1 m at 40 m/s is 2.5 ms back
+        assert!((crossing.translation - Vec3::new(0.1, 0.7025, front)).length() < 1e-4);
+        assert!((crossing.time_elapsed - 0.4975).abs() < 1e-6);
+        assert!((crossing.location.y - 0.7025).abs() < 1e-4);
+        assert!(crossing.in_strike_zone);
+    }
+
+    #[test]
+    fn location_is_as_the_catcher_sees_it() {
+        // bevy +x is the catcher's left, looking out towards the pitcher
+        let front = HomePlate::default().front();
+        let crossing = crossings(Vec3::new(0.1, 0.7, front - 0.1), Vec3::new(0., 0., -40.))[0];
+        assert!((crossing.location.x + 0.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn balls_short_of_the_plate_or_going_away_are_not_measured() {
+        let front = HomePlate::default().front();
+        assert!(crossings(Vec3::new(0., 0.7, front + 0.1), Vec3::new(0., 0., -40.)).is_empty());
+        assert!(crossings(Vec3::new(0., 0.7, front - 0.1), Vec3::new(0., 0., 40.)).is_empty());
+    }
+
+    #[test]
+    fn strike_zone_edges_count_the_ball_radius() {
+        let zone = StrikeZone::default();
+        let edge = PLATE_WIDTH / 2. + RADIUS;
+        let middle = (zone.top + zone.bottom) / 2.;
+        for x in [edge, -edge] {
+            assert!(zone.contains(Vec2::new(x, middle)));
+            assert!(!zone.contains(Vec2::new(x * 1.001, middle)));
+        }
+        assert!(zone.contains(Vec2::new(0., zone.top + RADIUS)));
+        assert!(!zone.contains(Vec2::new(0., zone.top + RADIUS + 0.001)));
+        assert!(zone.contains(Vec2::new(0., zone.bottom - RADIUS)));
+        assert!(!zone.contains(Vec2::new(0., zone.bottom - RADIUS - 0.001)));
+    }
+}
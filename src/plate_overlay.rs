@@ -0,0 +1,152 @@
+use crate::*;
+use std::collections::VecDeque;
+
+/// draws home plate, the strike zone and where the last pitches crossed the plate.
+/// crossings come from `PlateCrossingEvent`, what's drawn is set by `PlateOverlay`
+pub struct PlateOverlayPlugin;
+
+impl Plugin for PlateOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlateOverlay>();
+        app.init_resource::<PlateCrossings>();
+        app.register_type::<PlateOverlay>();
+
+        app.add_systems(
+            PostUpdate,
+            (record_plate_crossings, draw_plate_overlay)
+                .chain()
+                .run_if(|overlay: Res<PlateOverlay>| overlay.enabled),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CrossingColor {
+    /// by the ball's `PitchType`
+    #[default]
+    PitchType,
+    /// strikes and balls
+    Outcome,
+}
+
+#[derive(Debug, Clone, Copy, Resource, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlateOverlay {
+    pub enabled: bool,
+    // crossings kept on screen
+    pub max_crossings: usize,
+    pub color: CrossingColor,
+    // splits the zone into 3x3
+    pub zone_grid: bool,
+}
+
+impl Default for PlateOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_crossings: 20,
+            color: CrossingColor::default(),
+            zone_grid: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PlateCrossing {
+    pub event: PlateCrossingEvent,
+    // `None` when the ball had no `PitchType`
+    pub pitch_type: Option<PitchType>,
+}
+
+/// the last `PlateOverlay::max_crossings` crossings, oldest first
+#[derive(Debug, Clone, Default, Resource)]
+pub struct PlateCrossings(pub VecDeque<PlateCrossing>);
+
+const PLATE_COLOR: Color = Color::srgb(1., 1., 1.);
+const ZONE_COLOR: Color = Color::srgb(1., 1., 1.);
+const ZONE_GRID_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+const STRIKE_COLOR: Color = Color::srgb(1., 0.2, 0.2);
+const BALL_COLOR: Color = Color::srgb(0.2, 0.6, 1.);
+
+impl PitchType {
+    pub fn color(&self) -> Color {
+        match self {
+            Self::FourSeam => Color::srgb(0.84, 0.15, 0.15),
+            Self::TwoSeam => Color::srgb(1., 0.5, 0.),
+            Self::Sinker => Color::srgb(1., 0.75, 0.),
+            Self::Cutter => Color::srgb(0.55, 0.3, 0.15),
+            Self::Slider => Color::srgb(0.9, 0.9, 0.2),
+            Self::Sweeper => Color::srgb(0.8, 1., 0.3),
+            Self::Curveball => Color::srgb(0.2, 0.85, 0.85),
+            Self::Changeup => Color::srgb(0.2, 0.8, 0.2),
+            Self::Splitter => Color::srgb(0.3, 0.6, 0.5),
+            Self::Knuckleball => Color::srgb(0.6, 0.3, 0.9),
+            Self::Other => Color::srgb(0.7, 0.7, 0.7),
+        }
+    }
+}
+
+fn record_plate_crossings(
+    overlay: Res<PlateOverlay>,
+    mut crossings: ResMut<PlateCrossings>,
+    mut ev_plate_crossing_event: EventReader<PlateCrossingEvent>,
+    query_pitch_type: Query<&PitchType>,
+) {
+    for event in ev_plate_crossing_event.read() {
+        crossings.0.push_back(PlateCrossing {
+            event: *event,
+            pitch_type: query_pitch_type.get(event.entity).ok().copied(),
+        });
+    }
+    while crossings.0.len() > overlay.max_crossings {
+        crossings.0.pop_front();
+    }
+}
+
+fn draw_plate_overlay(
+    mut gizmos: Gizmos,
+    overlay: Res<PlateOverlay>,
+    crossings: Res<PlateCrossings>,
+    home_plate: Res<HomePlate>,
+    strike_zone: Res<StrikeZone>,
+) {
+    let outline = home_plate.outline();
+    gizmos.linestrip(outline.iter().chain(outline.first()).copied(), PLATE_COLOR);
+
+    // the zone stands on the front edge of the plate
+    let front = outline[0].z;
+    let left = outline[0].x;
+    let right = outline[1].x;
+    let bottom = home_plate.translation.y + strike_zone.bottom;
+    let top = home_plate.translation.y + strike_zone.top;
+    let zone_point = |x: f32, y: f32| Vec3::new(x, y, front);
+    gizmos.linestrip(
+        [
+            zone_point(left, bottom),
+            zone_point(right, bottom),
+            zone_point(right, top),
+            zone_point(left, top),
+            zone_point(left, bottom),
+        ],
+        ZONE_COLOR,
+    );
+    if overlay.zone_grid {
+        for i in 1..3 {
+            let t = i as f32 / 3.;
+            let x = left + (right - left) * t;
+            let y = bottom + (top - bottom) * t;
+            gizmos.line(zone_point(x, bottom), zone_point(x, top), ZONE_GRID_COLOR);
+            gizmos.line(zone_point(left, y), zone_point(right, y), ZONE_GRID_COLOR);
+        }
+    }
+
+    for crossing in &crossings.0 {
+        let color = match overlay.color {
+            CrossingColor::PitchType => crossing.pitch_type.unwrap_or(PitchType::Other).color(),
+            CrossingColor::Outcome if crossing.event.in_strike_zone => STRIKE_COLOR,
+            CrossingColor::Outcome => BALL_COLOR,
+        };
+        gizmos.circle(crossing.event.translation, Dir3::Z, RADIUS, color);
+    }
+}
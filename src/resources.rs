@@ -1,17 +1,218 @@
 use crate::*;
+use std::fmt;
 
-#[derive(Debug, Resource)]
-pub(crate) struct BaseballPluginConfig {
+#[derive(Debug, Clone, Resource)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BaseballPluginConfig {
     pub ssw_on: bool,
     pub magnus_on: bool,
     pub drag_on: bool,
+    pub integration_mode: FlightIntegrationMode,
+    pub integrator: Integrator,
     pub ssw: SeamShiftedWake,
+    pub atmosphere: Atmosphere,
+    pub spin_decay: SpinDecay,
+    pub knuckleball: Knuckleball,
+    /// balls are stepped on all cores once there are more than this many of them
+    pub parallel_threshold: usize,
+    /// used for every ball without its own `SeamGeometry` component
+    pub seam_geometry: SeamGeometry,
+    /// used for every ball without its own `AerodynamicModelHandle`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub model: AerodynamicModelHandle,
 }
 
+/// air the ball flies through, in imperial units
+#[derive(Debug, Copy, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Atmosphere {
+    // in lb/ft^3
+    pub density: f64,
+    // in ft^2/s
+    pub kinematic_viscosity: f64,
+}
+
+impl Default for Atmosphere {
+    fn default() -> Self {
+        Self {
+            density: RHO,
+            kinematic_viscosity: NU,
+        }
+    }
+}
+
+impl Atmosphere {
+    /// acceleration per (coefficient * speed^2)
+    pub(crate) fn c_0(&self) -> f64 {
+        0.5 * self.density * AREA / MASS_OZ
+    }
+
+    /// speed in ft/s
+    pub fn reynolds_number(&self, speed: f64) -> f64 {
+        speed * DIAMETER / self.kinematic_viscosity
+    }
+}
+
+/// spin decay from aerodynamic torque, dw/dt = -rate * (v / r) * w
+#[derive(Debug, Copy, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpinDecay {
+    // dimensionless, 0 turns decay off
+    pub rate: f64,
+}
+
+impl Default for SpinDecay {
+    fn default() -> Self {
+        // Nathan's trajectory calculator, a time constant of ~46 s at 90 mph
+        Self { rate: 2e-5 }
+    }
+}
+
+impl SpinDecay {
+    /// dw/dt per w at `speed` (ft/s)
+    pub(crate) fn rate_at(&self, speed: f64) -> f64 {
+        -self.rate * speed / (DIAMETER / 2.)
+    }
+
+    /// how much of the spin is left after `delta_t` at `speed` (ft/s)
+    pub(crate) fn factor(&self, speed: f64, delta_t: f64) -> f64 {
+        (self.rate_at(speed) * delta_t).exp()
+    }
+}
+
+/// how the simulated flight drives the ball entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FlightIntegrationMode {
+    /// teleports the `Transform` every step. use with a `RigidBody::Kinematic` ball,
+    /// its `LinearVelocity` is zeroed while aerodynamics are active
+    Kinematic,
+    /// overwrites `LinearVelocity` every step and lets avian move the body
+    VelocityDriven,
+    /// applies the aerodynamics and gravity as an `ExternalForce`
+    #[default]
+    ForceDriven,
+    /// no avian body at all. `Transform` and, if present, `LinearVelocity` are written from the
+    /// internal state. the ball only needs `BaseballFlightState` and `Transform`. `LinearVelocity`
+    /// and `AngularVelocity` are written when present, without them the ball is thrown with the
+    /// `v` and `spin` already set on its state
+    Internal,
+}
+
+/// how the flight state is stepped through time
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Integrator {
+    /// classic rk4 at a fixed step, in s. leftover time is carried to the next update
+    Rk4 { step: f64 },
+    /// embedded dormand-prince 5(4). the step grows and shrinks to keep the local error of
+    /// position (ft) and velocity (ft/s) under `absolute_tolerance + relative_tolerance * |y|`.
+    /// as with rk4 leftover time is carried to the next update, so the flight can trail the
+    /// frame by up to a step
+    DormandPrince {
+        relative_tolerance: f64,
+        absolute_tolerance: f64,
+        // in s
+        min_step: f64,
+        max_step: f64,
+    },
+}
+
+impl Default for Integrator {
+    fn default() -> Self {
+        Self::Rk4 { step: T_STEP }
+    }
+}
+
+impl Integrator {
+    pub fn dormand_prince() -> Self {
+        Self::DormandPrince {
+            relative_tolerance: 1e-6,
+            absolute_tolerance: 1e-6,
+            min_step: 1e-5,
+            max_step: 0.01,
+        }
+    }
+
+    // smallest step the integrator takes, in s
+    pub(crate) fn min_step(&self) -> f64 {
+        match *self {
+            Self::Rk4 { step } => step,
+            Self::DormandPrince { min_step, .. } => min_step,
+        }
+    }
+
+    /// checks the steps and tolerances can actually advance the flight
+    pub fn validate(&self) -> Result<(), IntegratorError> {
+        match *self {
+            Self::Rk4 { step } => {
+                if step.is_nan() || step <= 0. || step.is_infinite() {
+                    return Err(IntegratorError::NonPositiveStep(step));
+                }
+            }
+            Self::DormandPrince {
+                relative_tolerance,
+                absolute_tolerance,
+                min_step,
+                max_step,
+            } => {
+                if min_step.is_nan() || min_step <= 0. || min_step.is_infinite() {
+                    return Err(IntegratorError::NonPositiveStep(min_step));
+                }
+                if max_step.is_nan() || max_step < min_step || max_step.is_infinite() {
+                    return Err(IntegratorError::StepRange { min_step, max_step });
+                }
+                if relative_tolerance.is_nan()
+                    || absolute_tolerance.is_nan()
+                    || relative_tolerance < 0.
+                    || absolute_tolerance < 0.
+                {
+                    return Err(IntegratorError::NegativeTolerance);
+                }
+                if relative_tolerance == 0. && absolute_tolerance == 0. {
+                    return Err(IntegratorError::ZeroTolerance);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IntegratorError {
+    NonPositiveStep(f64),
+    StepRange { min_step: f64, max_step: f64 },
+    NegativeTolerance,
+    ZeroTolerance,
+}
+
+impl fmt::Display for IntegratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonPositiveStep(step) => {
+                write!(f, "integrator step {step} is not a positive number")
+            }
+            Self::StepRange { min_step, max_step } => {
+                write!(
+                    f,
+                    "max step {max_step} is not a number at least min step {min_step}"
+                )
+            }
+            Self::NegativeTolerance => {
+                write!(f, "integrator tolerances must not be negative or nan")
+            }
+            Self::ZeroTolerance => write!(f, "integrator tolerances can't both be zero"),
+        }
+    }
+}
+
+impl std::error::Error for IntegratorError {}
+
 // probably should be a resource
 /// seam shifted wake parameters
 #[derive(Debug, Copy, Clone)]
-pub(crate) struct SeamShiftedWake {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SeamShiftedWake {
     // this number effects how much the separation location will change based on the spin rate. Bigger, Move shift allows for the moving the effectiveness of the seams forwards or backwards.
     pub seam_shift_factor: f64,
     // in rad
@@ -20,6 +221,9 @@ pub(crate) struct SeamShiftedWake {
     pub activation_shift: f64,
     // in rad
     pub separated_flow_range: f64,
+    // also shift the seams around the flow axis by the gyro spin, on top of the fixed shift.
+    // off gives the original ssw
+    pub gyro_seam_shift: bool,
 }
 
 impl Default for SeamShiftedWake {
@@ -29,6 +233,7 @@ impl Default for SeamShiftedWake {
             angle_of_activation: 5. * PI_64 / 180.,
             activation_shift: 0.21,
             separated_flow_range: 35. * PI_64 / 180.,
+            gyro_seam_shift: true,
         }
     }
 }
@@ -58,7 +263,15 @@ impl Default for BaseballPluginConfig {
             ssw_on: true,
             magnus_on: true,
             drag_on: true,
+            integration_mode: FlightIntegrationMode::default(),
+            integrator: Integrator::default(),
             ssw: SeamShiftedWake::default(),
+            atmosphere: Atmosphere::default(),
+            spin_decay: SpinDecay::default(),
+            knuckleball: Knuckleball::default(),
+            parallel_threshold: 32,
+            seam_geometry: SeamGeometry::default(),
+            model: AerodynamicModelHandle::default(),
         }
     }
 }
@@ -0,0 +1,260 @@
+use crate::*;
+use std::fmt;
+
+/// seam centerline curves
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SeamCurve {
+    /// the tennis ball curve the umba calculator uses
+    TennisBall,
+    /// hand tuned spherical curve with narrower necks and wider lobes than the tennis ball,
+    /// closer in looks to a baseball seam but not fitted to one.
+    /// longitude `t + 0.4 sin 4t`, latitude `asin(0.93 cos 2t)`
+    Approximate,
+}
+
+/// closed seam centerline on a ball of `SEAM_DIAMETER`, in inches, before it's oriented.
+/// each point carries the share of the seam length around it, so the seam shifted wake force
+/// stays the same however finely the seam is sampled
+#[derive(Debug, Clone, Component, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SeamGeometry {
+    pub points: Vec<DVec3>,
+    // sums to N_SEAMS, so a uniformly sampled 108 point seam has weights of 1
+    pub weights: Vec<f64>,
+}
+
+impl Default for SeamGeometry {
+    fn default() -> Self {
+        Self::from_curve(SeamCurve::TennisBall, N_SEAMS)
+    }
+}
+
+#[derive(Debug)]
+pub enum SeamGeometryError {
+    MissingPositions,
+    TooFewPoints(usize),
+}
+
+impl fmt::Display for SeamGeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPositions => write!(f, "seam mesh has no float3 vertex positions"),
+            Self::TooFewPoints(n) => write!(f, "a seam needs at least 3 points, got {n}"),
+        }
+    }
+}
+
+impl std::error::Error for SeamGeometryError {}
+
+impl SeamGeometry {
+    pub fn from_curve(curve: SeamCurve, resolution: usize) -> Self {
+        let points = (0..resolution)
+            .map(|i| {
+                let alpha = (PI_64 * 2.) * (i as f64 / resolution as f64);
+                let point = match curve {
+                    SeamCurve::TennisBall => {
+                        let x = (1. / 13.) * (9. * f64::cos(alpha) - 4. * f64::cos(3. * alpha));
+                        let y = (1. / 13.) * (9. * f64::sin(alpha) + 4. * f64::sin(3. * alpha));
+                        let z = (12. / 13.) * f64::cos(2. * alpha);
+                        DVec3::new(x, y, z)
+                    }
+                    SeamCurve::Approximate => {
+                        let longitude = alpha + 0.4 * (4. * alpha).sin();
+                        let latitude = (0.93 * (2. * alpha).cos()).asin();
+                        DVec3::new(
+                            latitude.cos() * longitude.cos(),
+                            latitude.cos() * longitude.sin(),
+                            latitude.sin(),
+                        )
+                    }
+                };
+                point * (SEAM_DIAMETER / 2.)
+            })
+            .collect::<Vec<_>>();
+
+        Self::from_points(points)
+    }
+
+    /// points are taken as a closed loop in order
+    pub fn from_points(points: Vec<DVec3>) -> Self {
+        let n = points.len();
+        let mut weights = (0..n)
+            .map(|i| {
+                let prev = points[(i + n - 1) % n];
+                let next = points[(i + 1) % n];
+                0.5 * (points[i].distance(prev) + points[i].distance(next))
+            })
+            .collect::<Vec<_>>();
+
+        let total = weights.iter().sum::<f64>();
+        if total > 0. {
+            weights
+                .iter_mut()
+                .for_each(|weight| *weight *= N_SEAMS as f64 / total);
+        }
+
+        Self { points, weights }
+    }
+
+    /// extracts the centerline of a seam (stitching) mesh, e.g. from a glTF ball.
+    /// vertices are projected on the ball, chained nearest first, smoothed across the stitch
+    /// width and resampled evenly
+    #[cfg(feature = "render")]
+    pub fn from_mesh(mesh: &Mesh, resolution: usize) -> Result<Self, SeamGeometryError> {
+        let positions = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|positions| positions.as_float3())
+            .ok_or(SeamGeometryError::MissingPositions)?;
+
+        let mut remaining = positions
+            .iter()
+            .map(|&position| Vec3::from(position).as_dvec3().normalize_or_zero())
+            .filter(|point| *point != DVec3::ZERO)
+            .collect::<Vec<_>>();
+        if remaining.len() < 3 || resolution < 3 {
+            return Err(SeamGeometryError::TooFewPoints(
+                remaining.len().min(resolution),
+            ));
+        }
+
+        let mut chain = vec![remaining.swap_remove(0)];
+        while !remaining.is_empty() {
+            let last = chain[chain.len() - 1];
+            let (nearest, _) = remaining
+                .iter()
+                .enumerate()
+                .map(|(i, point)| (i, point.distance_squared(last)))
+                .fold((0, f64::MAX), |best, candidate| {
+                    if candidate.1 < best.1 {
+                        candidate
+                    } else {
+                        best
+                    }
+                });
+            chain.push(remaining.swap_remove(nearest));
+        }
+
+        // the chain zigzags across the stitches, averaging takes out the width
+        let window = (chain.len() / resolution).max(1);
+        let n = chain.len();
+        let smoothed = (0..n)
+            .map(|i| {
+                (0..window)
+                    .map(|k| chain[(i + k + n - window / 2) % n])
+                    .sum::<DVec3>()
+                    .normalize_or_zero()
+            })
+            .collect::<Vec<_>>();
+
+        let points = Self::resample(&smoothed, resolution)
+            .into_iter()
+            .map(|point| point * (SEAM_DIAMETER / 2.))
+            .collect();
+
+        Ok(Self::from_points(points))
+    }
+
+    // evenly spaced by arc length along the closed loop
+    #[cfg(feature = "render")]
+    fn resample(points: &[DVec3], resolution: usize) -> Vec<DVec3> {
+        let n = points.len();
+        let lengths = (0..n)
+            .map(|i| points[i].distance(points[(i + 1) % n]))
+            .collect::<Vec<_>>();
+        let total = lengths.iter().sum::<f64>();
+
+        let mut resampled = Vec::with_capacity(resolution);
+        let mut segment = 0;
+        let mut travelled = 0.;
+        for i in 0..resolution {
+            let target = total * i as f64 / resolution as f64;
+            while segment < n - 1 && travelled + lengths[segment] < target {
+                travelled += lengths[segment];
+                segment += 1;
+            }
+            let t = if lengths[segment] > 0. {
+                (target - travelled) / lengths[segment]
+            } else {
+                0.
+            };
+            resampled.push(
+                points[segment]
+                    .lerp(points[(segment + 1) % n], t)
+                    .normalize_or_zero(),
+            );
+        }
+        resampled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "render")]
+    use bevy::render::{mesh::PrimitiveTopology, render_asset::RenderAssetUsages};
+
+    #[test]
+    fn weights_cover_the_seam_length() {
+        for curve in [SeamCurve::TennisBall, SeamCurve::Approximate] {
+            for resolution in [N_SEAMS, N_SEAMS * 4] {
+                let seams = SeamGeometry::from_curve(curve, resolution);
+                assert_eq!(seams.points.len(), resolution);
+                let total = seams.weights.iter().sum::<f64>();
+                assert!((total - N_SEAMS as f64).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[cfg(feature = "render")]
+    fn point_mesh(points: Vec<Vec3>) -> Mesh {
+        Mesh::new(PrimitiveTopology::PointList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, points)
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn from_mesh_finds_the_stitched_centerline() {
+        // stitches zigzag 0.03 in across the seam, a bit wider than they are apart
+        let curve = SeamGeometry::from_curve(SeamCurve::TennisBall, N_SEAMS * 4);
+        let n = curve.points.len();
+        let stitches = (0..n)
+            .map(|i| {
+                let (point, next) = (curve.points[i], curve.points[(i + 1) % n]);
+                let across = point.cross(next - point).normalize();
+                let side = if i % 2 == 0 { 0.015 } else { -0.015 };
+                (point + across * side).as_vec3()
+            })
+            .collect();
+
+        let seams = SeamGeometry::from_mesh(&point_mesh(stitches), N_SEAMS).unwrap();
+        let centerline = SeamGeometry::from_curve(SeamCurve::TennisBall, N_SEAMS * 64);
+        assert_eq!(seams.points.len(), N_SEAMS);
+        assert!((seams.weights.iter().sum::<f64>() - N_SEAMS as f64).abs() < 1e-9);
+        for point in &seams.points {
+            assert!((point.length() - SEAM_DIAMETER / 2.).abs() < 1e-9);
+            let off = centerline
+                .points
+                .iter()
+                .map(|seam| seam.distance(*point))
+                .fold(f64::MAX, f64::min);
+            assert!(off < 0.005, "{point} is {off} in off the seam");
+        }
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn from_mesh_rejects_unusable_meshes() {
+        let empty = Mesh::new(PrimitiveTopology::PointList, RenderAssetUsages::default());
+        assert!(matches!(
+            SeamGeometry::from_mesh(&empty, N_SEAMS),
+            Err(SeamGeometryError::MissingPositions)
+        ));
+
+        let two = point_mesh(vec![Vec3::X, Vec3::Y]);
+        assert!(matches!(
+            SeamGeometry::from_mesh(&two, N_SEAMS),
+            Err(SeamGeometryError::TooFewPoints(2))
+        ));
+    }
+}
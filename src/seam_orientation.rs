@@ -0,0 +1,176 @@
+use crate::*;
+
+/// how the seams sit on the ball when aerodynamics are activated
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SeamOrientation {
+    /// the umba calculator's two angles relative to the spin axis, in rad
+    Angles {
+        y: f32,
+        z: f32,
+    },
+    /// absolute rotation of the `SeamGeometry` in the baseball coord
+    Quat(DQuat),
+    /// the ball entity's `Transform::rotation`, so a modeled ball flies with the seams it's rendered with
+    FromTransform,
+    Named(NamedSeamOrientation),
+}
+
+impl Default for SeamOrientation {
+    fn default() -> Self {
+        Self::Angles { y: 0., z: 0. }
+    }
+}
+
+/// common grips, in the two angle form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NamedSeamOrientation {
+    FourSeam,
+    TwoSeam,
+    OneSeam,
+}
+
+impl NamedSeamOrientation {
+    pub const ALL: [Self; 3] = [Self::FourSeam, Self::TwoSeam, Self::OneSeam];
+
+    /// the grip whose seams are closest to `quat`, if within `tolerance` rad of it.
+    /// `spin` and `quat` are in the baseball coord
+    pub fn from_quat(spin: DVec3, quat: DQuat, tolerance: f64) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .map(|named| {
+                let (y, z) = named.angles();
+                let angle = SeamOrientation::quat_from_angles(spin, y, z).angle_between(quat);
+                (named, angle)
+            })
+            .filter(|(_, angle)| *angle <= tolerance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(named, _)| named)
+    }
+
+    /// the grip closest to the two angle form, (y, z) in rad, if within `tolerance` rad of it
+    pub fn from_angles(y: f32, z: f32, tolerance: f64) -> Option<Self> {
+        Self::from_quat(
+            DVec3::X,
+            SeamOrientation::quat_from_angles(DVec3::X, y, z),
+            tolerance,
+        )
+    }
+
+    /// (y, z) in rad
+    pub fn angles(&self) -> (f32, f32) {
+        match self {
+            Self::FourSeam => (0., PI_32 / 2.),
+            Self::TwoSeam => (0., 0.),
+            Self::OneSeam => (PI_32 / 2., 0.),
+        }
+    }
+}
+
+// takes the seam curve's own frame to one where the x axis is the axis the angles are relative to
+fn seam_base_rotation() -> DQuat {
+    DQuat::from_rotation_y(PI_64 / 2.) * DQuat::from_rotation_x(-PI_64 / 2.)
+}
+
+impl SeamOrientation {
+    /// seam rotation in the baseball coord. `spin` is in the baseball coord, `rotation` is the
+    /// entity's bevy rotation
+    pub fn to_quat(&self, spin: DVec3, rotation: Quat) -> DQuat {
+        match *self {
+            Self::Angles { y, z } => Self::quat_from_angles(spin, y, z),
+            Self::Quat(quat) => quat,
+            Self::FromTransform => rotation_from_bevy_to_baseball_coord(rotation),
+            Self::Named(named) => {
+                let (y, z) = named.angles();
+                Self::quat_from_angles(spin, y, z)
+            }
+        }
+    }
+
+    /// the two angle form, (y, z) in rad. roll around the spin axis can't be expressed in it and is dropped
+    pub fn to_angles(&self, spin: DVec3, rotation: Quat) -> (f32, f32) {
+        match *self {
+            Self::Angles { y, z } => (y, z),
+            Self::Named(named) => named.angles(),
+            _ => Self::angles_from_quat(spin, self.to_quat(spin, rotation)),
+        }
+    }
+
+    /// the grip these seams are, if within `tolerance` rad of one
+    pub fn named(
+        &self,
+        spin: DVec3,
+        rotation: Quat,
+        tolerance: f64,
+    ) -> Option<NamedSeamOrientation> {
+        match *self {
+            Self::Named(named) => Some(named),
+            _ => NamedSeamOrientation::from_quat(spin, self.to_quat(spin, rotation), tolerance),
+        }
+    }
+
+    pub fn quat_from_angles(spin: DVec3, y: f32, z: f32) -> DQuat {
+        // X axis of seams space should be the axis of rotation
+        DQuat::from_rotation_arc(DVec3::X, spin.normalize_or(DVec3::X))
+            * DQuat::from_rotation_z(-z as f64)
+            * DQuat::from_rotation_y(y as f64)
+            * seam_base_rotation()
+    }
+
+    pub fn angles_from_quat(spin: DVec3, quat: DQuat) -> (f32, f32) {
+        let relative = DQuat::from_rotation_arc(DVec3::X, spin.normalize_or(DVec3::X)).inverse()
+            * quat
+            * seam_base_rotation().inverse();
+        let (minus_z, y, _roll) = relative.to_euler(EulerRot::ZYX);
+        (y as f32, -minus_z as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f64 = 1e-3;
+
+    #[test]
+    fn named_round_trips_through_quat() {
+        let spin = DVec3::new(-1., 0.3, 0.2);
+        for named in NamedSeamOrientation::ALL {
+            let quat = SeamOrientation::Named(named).to_quat(spin, Quat::IDENTITY);
+            assert_eq!(
+                NamedSeamOrientation::from_quat(spin, quat, TOLERANCE),
+                Some(named)
+            );
+            assert_eq!(
+                SeamOrientation::Quat(quat).named(spin, Quat::IDENTITY, TOLERANCE),
+                Some(named)
+            );
+        }
+    }
+
+    #[test]
+    fn named_round_trips_through_angles() {
+        for named in NamedSeamOrientation::ALL {
+            let (y, z) = named.angles();
+            assert_eq!(
+                NamedSeamOrientation::from_angles(y, z, TOLERANCE),
+                Some(named)
+            );
+        }
+    }
+
+    #[test]
+    fn nearby_seams_match_within_the_tolerance_only() {
+        let (y, z) = NamedSeamOrientation::FourSeam.angles();
+        let off = 0.05;
+        assert_eq!(
+            NamedSeamOrientation::from_angles(y, z + off as f32, 2. * off),
+            Some(NamedSeamOrientation::FourSeam)
+        );
+        assert_eq!(
+            NamedSeamOrientation::from_angles(y, z + off as f32, off / 2.),
+            None
+        );
+    }
+}
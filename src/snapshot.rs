@@ -0,0 +1,50 @@
+use crate::*;
+
+/// everything needed to resume a ball mid-flight exactly where it was captured.
+/// the flight state holds the seams, spin and elapsed time, the rest is the avian body.
+/// velocities a ball doesn't have, e.g. in `FlightIntegrationMode::Internal`, are left out
+#[derive(Debug, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BaseballFlightSnapshot {
+    pub state: BaseballFlightState,
+    // bevy coord
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub linear_velocity: Vec3,
+    pub angular_velocity: Vec3,
+}
+
+impl BaseballFlightSnapshot {
+    pub fn capture(
+        state: &BaseballFlightState,
+        transform: &Transform,
+        linear_velocity: Option<&LinearVelocity>,
+        angular_velocity: Option<&AngularVelocity>,
+    ) -> Self {
+        Self {
+            state: state.clone(),
+            translation: transform.translation,
+            rotation: transform.rotation,
+            linear_velocity: linear_velocity.map_or(Vec3::ZERO, |velocity| velocity.0),
+            angular_velocity: angular_velocity.map_or(Vec3::ZERO, |velocity| velocity.0),
+        }
+    }
+
+    pub fn restore(
+        &self,
+        state: &mut BaseballFlightState,
+        transform: &mut Transform,
+        linear_velocity: Option<&mut LinearVelocity>,
+        angular_velocity: Option<&mut AngularVelocity>,
+    ) {
+        *state = self.state.clone();
+        transform.translation = self.translation;
+        transform.rotation = self.rotation;
+        if let Some(linear_velocity) = linear_velocity {
+            linear_velocity.0 = self.linear_velocity;
+        }
+        if let Some(angular_velocity) = angular_velocity {
+            angular_velocity.0 = self.angular_velocity;
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use crate::*;
+use std::fmt;
+
+/// which arm the pitch is thrown with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Handedness {
+    #[default]
+    Right,
+    Left,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpinAxisError {
+    HourOutOfRange(i8),
+    MinuteOutOfRange(i8),
+    DegreesOutOfRange(f64),
+    SpinEfficiencyOutOfRange(f64),
+    NonFinite,
+    ZeroVector,
+}
+
+impl fmt::Display for SpinAxisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HourOutOfRange(h) => write!(f, "tilt hour {h} is not within 1..=12"),
+            Self::MinuteOutOfRange(m) => write!(f, "tilt minute {m} is not within 0..=59"),
+            Self::DegreesOutOfRange(degrees) => {
+                write!(f, "spin axis of {degrees} degrees is not within 0..=360")
+            }
+            Self::SpinEfficiencyOutOfRange(efficiency) => {
+                write!(f, "spin efficiency {efficiency} is not within 0..=1")
+            }
+            Self::NonFinite => write!(f, "spin axis is not finite"),
+            Self::ZeroVector => write!(f, "spin axis vector has no direction"),
+        }
+    }
+}
+
+impl std::error::Error for SpinAxisError {}
+
+/// unit spin axis in the baseball coord, for a pitch travelling along -y.
+///
+/// the clock tilt is seen from behind home plate, 12:00 being pure backspin. the statcast
+/// `spin_axis` is the same angle in degrees, turned so that 180 is 12:00 and 210 is 1:00.
+/// spin efficiency is the share of the spin that isn't gyro spin
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpinAxis(DVec3);
+
+impl SpinAxis {
+    pub fn from_clock(
+        h: i8,
+        m: i8,
+        spin_efficiency: f64,
+        gyro_pole: GyroPole,
+    ) -> Result<Self, SpinAxisError> {
+        let tilt = Tilt::try_from_hour_minutes(h, m)?;
+        Self::from_tilt(tilt.get() as f64, spin_efficiency, gyro_pole)
+    }
+
+    pub fn from_statcast_degrees(
+        degrees: f64,
+        spin_efficiency: f64,
+        gyro_pole: GyroPole,
+    ) -> Result<Self, SpinAxisError> {
+        if !degrees.is_finite() {
+            return Err(SpinAxisError::NonFinite);
+        }
+        if !(0. ..=360.).contains(&degrees) {
+            return Err(SpinAxisError::DegreesOutOfRange(degrees));
+        }
+        // 180 degrees is 12:00, which is a tilt of 3pi/2
+        Self::from_tilt((degrees - 270.).to_radians(), spin_efficiency, gyro_pole)
+    }
+
+    /// any non zero vector in the baseball coord, it's normalized
+    pub fn from_vector(axis: DVec3) -> Result<Self, SpinAxisError> {
+        if !axis.is_finite() {
+            return Err(SpinAxisError::NonFinite);
+        }
+        axis.try_normalize()
+            .map(Self)
+            .ok_or(SpinAxisError::ZeroVector)
+    }
+
+    // tilt in rad as in `Tilt`, 0 at 3:00
+    fn from_tilt(
+        tilt: f64,
+        spin_efficiency: f64,
+        gyro_pole: GyroPole,
+    ) -> Result<Self, SpinAxisError> {
+        if !spin_efficiency.is_finite() {
+            return Err(SpinAxisError::NonFinite);
+        }
+        if !(0. ..=1.).contains(&spin_efficiency) {
+            return Err(SpinAxisError::SpinEfficiencyOutOfRange(spin_efficiency));
+        }
+        // a right pole gyro points along the flight
+        let gyro = (1. - spin_efficiency.powi(2)).sqrt()
+            * match gyro_pole {
+                GyroPole::Right => -1.,
+                GyroPole::Left => 1.,
+            };
+        Ok(Self(DVec3::new(
+            spin_efficiency * tilt.sin(),
+            gyro,
+            -spin_efficiency * tilt.cos(),
+        )))
+    }
+
+    pub fn get(&self) -> DVec3 {
+        self.0
+    }
+
+    /// angular velocity in rad/s, in the baseball coord
+    pub fn spin(&self, spin_rate: f64) -> DVec3 {
+        self.0 * spin_rate
+    }
+
+    pub fn spin_efficiency(&self) -> f64 {
+        self.0.x.hypot(self.0.z).min(1.)
+    }
+
+    pub fn gyro_pole(&self) -> GyroPole {
+        if self.0.y <= 0. {
+            GyroPole::Right
+        } else {
+            GyroPole::Left
+        }
+    }
+
+    // clock angle in rad, 0 at 12:00 and increasing clockwise, within 0..2pi
+    fn clock_angle(&self) -> f64 {
+        let tilt = self.0.x.atan2(-self.0.z);
+        (tilt + PI_64 / 2.).rem_euclid(2. * PI_64)
+    }
+
+    /// (hour, minute) rounded to the minute. meaningless for pure gyro spin
+    pub fn clock(&self) -> (i8, i8) {
+        let minutes = (self.clock_angle() / (2. * PI_64) * 720.).round() as i32 % 720;
+        let h = minutes / 60;
+        ((if h == 0 { 12 } else { h }) as i8, (minutes % 60) as i8)
+    }
+
+    pub fn statcast_degrees(&self) -> f64 {
+        (self.clock_angle().to_degrees() + 180.).rem_euclid(360.)
+    }
+
+    /// the same pitch thrown with the other arm, mirrored across the y-z plane.
+    /// 1:00 becomes 11:00 and the gyro pole flips
+    pub fn mirrored(&self) -> Self {
+        // spin is an axial vector, so mirroring x flips the other two components
+        Self(DVec3::new(self.0.x, -self.0.y, -self.0.z))
+    }
+
+    /// takes an axis given for a right handed pitcher to the one for `handedness`
+    pub fn for_handedness(&self, handedness: Handedness) -> Self {
+        match handedness {
+            Handedness::Right => *self,
+            Handedness::Left => self.mirrored(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the clock goes through `Tilt`'s f32
+    const EPSILON: f64 = 1e-5;
+
+    fn assert_same_axis(a: SpinAxis, b: SpinAxis) {
+        assert!(
+            a.get().abs_diff_eq(b.get(), EPSILON),
+            "{:?} vs {:?}",
+            a.get(),
+            b.get()
+        );
+    }
+
+    #[test]
+    fn statcast_degrees_and_clock_agree() {
+        for (degrees, (h, m)) in [
+            (180., (12, 0)),
+            (210., (1, 0)),
+            (90., (9, 0)),
+            (255., (2, 30)),
+        ] {
+            let from_degrees =
+                SpinAxis::from_statcast_degrees(degrees, 1., GyroPole::Right).unwrap();
+            let from_clock = SpinAxis::from_clock(h, m, 1., GyroPole::Right).unwrap();
+            assert_same_axis(from_degrees, from_clock);
+
+            assert_eq!(from_degrees.clock(), (h, m));
+            assert!((from_clock.statcast_degrees() - degrees).abs() < EPSILON);
+        }
+    }
+
+    #[test]
+    fn clock_round_trips() {
+        for (h, m) in [(12, 0), (1, 0), (3, 15), (6, 0), (11, 59)] {
+            let axis = SpinAxis::from_clock(h, m, 0.8, GyroPole::Left).unwrap();
+            assert_eq!(axis.clock(), (h, m));
+            assert!((axis.spin_efficiency() - 0.8).abs() < EPSILON);
+            assert!(matches!(axis.gyro_pole(), GyroPole::Left));
+        }
+    }
+
+    #[test]
+    fn mirrored_is_the_other_arm() {
+        let axis = SpinAxis::from_clock(1, 0, 0.9, GyroPole::Right).unwrap();
+        let mirrored = axis.mirrored();
+        assert_eq!(mirrored.clock(), (11, 0));
+        assert!(matches!(mirrored.gyro_pole(), GyroPole::Left));
+        assert!((mirrored.spin_efficiency() - 0.9).abs() < EPSILON);
+        assert_eq!(mirrored.mirrored(), axis);
+
+        assert_eq!(axis.for_handedness(Handedness::Right), axis);
+        assert_eq!(axis.for_handedness(Handedness::Left), mirrored);
+    }
+
+    #[test]
+    fn invalid_axes_are_rejected() {
+        assert_eq!(
+            SpinAxis::from_clock(13, 0, 1., GyroPole::Right),
+            Err(SpinAxisError::HourOutOfRange(13))
+        );
+        assert_eq!(
+            SpinAxis::from_clock(12, 60, 1., GyroPole::Right),
+            Err(SpinAxisError::MinuteOutOfRange(60))
+        );
+        assert_eq!(
+            SpinAxis::from_statcast_degrees(361., 1., GyroPole::Right),
+            Err(SpinAxisError::DegreesOutOfRange(361.))
+        );
+        assert_eq!(
+            SpinAxis::from_statcast_degrees(f64::NAN, 1., GyroPole::Right),
+            Err(SpinAxisError::NonFinite)
+        );
+        assert_eq!(
+            SpinAxis::from_clock(12, 0, 1.5, GyroPole::Right),
+            Err(SpinAxisError::SpinEfficiencyOutOfRange(1.5))
+        );
+        assert_eq!(
+            SpinAxis::from_clock(12, 0, f64::NAN, GyroPole::Right),
+            Err(SpinAxisError::NonFinite)
+        );
+        assert_eq!(
+            SpinAxis::from_vector(DVec3::ZERO),
+            Err(SpinAxisError::ZeroVector)
+        );
+        assert_eq!(
+            SpinAxis::from_vector(DVec3::new(f64::INFINITY, 0., 0.)),
+            Err(SpinAxisError::NonFinite)
+        );
+    }
+}
@@ -1,116 +1,393 @@
 use crate::*;
+use bevy::ecs::query::QueryItem;
 
-// option 1 - update transform
-pub(crate) fn _apply_physics_option_1(
-    time_fixed: Res<Time<Fixed>>,
-    time_physics: Res<Time<Physics>>,
+fn relative_speed(time_physics: &Option<Res<Time<Physics>>>) -> f64 {
+    time_physics
+        .as_ref()
+        .map_or(1., |time_physics| time_physics.relative_speed_f64())
+}
+
+type KinematicBall = (
+    &'static mut BaseballFlightState,
+    &'static mut Transform,
+    &'static mut AngularVelocity,
+    Option<&'static AerodynamicModelHandle>,
+);
+
+// FlightIntegrationMode::Kinematic - teleport the transform
+pub(crate) fn apply_kinematic(
+    time: Res<Time>,
+    time_physics: Option<Res<Time<Physics>>>,
     baseball_plugin_config: Res<BaseballPluginConfig>,
-    mut query_baseball: Query<(&mut BaseballFlightState, &mut Transform)>,
+    mut query_baseball: Query<KinematicBall>,
 ) {
     // 0.0167
-    let delta_t = time_fixed.delta_seconds_f64() * time_physics.relative_speed_f64();
-    for (mut state, mut transform) in &mut query_baseball {
-        state.update_state(&baseball_plugin_config, delta_t);
+    let delta_t = time.delta_seconds_f64() * relative_speed(&time_physics);
+    let config = &*baseball_plugin_config;
+    if query_baseball.iter().len() > config.parallel_threshold {
+        query_baseball
+            .par_iter_mut()
+            .for_each(|ball| kinematic_step(config, delta_t, ball));
+    } else {
+        query_baseball
+            .iter_mut()
+            .for_each(|ball| kinematic_step(config, delta_t, ball));
+    }
+}
+
+fn kinematic_step(
+    config: &BaseballPluginConfig,
+    delta_t: f64,
+    (mut state, mut transform, mut a_velo, model): QueryItem<KinematicBall>,
+) {
+    if state.active {
+        let model = model.unwrap_or(&config.model).get();
+        state.update_state(config, model, delta_t);
+        // left for `check_flight_finite` to deactivate
+        if !state.is_finite() {
+            return;
+        }
         transform.translation = state.translation.as_vec3().from_baseball_coord_to_bevy();
+        a_velo.0 = swap_coordinates_dvec3(&state.spin).as_vec3();
     }
 }
 
-// option 2 - update velocity
-pub(crate) fn _apply_physics_option_2(
-    time_fixed: Res<Time<Fixed>>,
-    time_physics: Res<Time<Physics>>,
+type InternalBall = (
+    &'static mut BaseballFlightState,
+    &'static mut Transform,
+    Option<&'static mut LinearVelocity>,
+    Option<&'static mut AngularVelocity>,
+    Option<&'static AerodynamicModelHandle>,
+);
+
+// FlightIntegrationMode::Internal - like kinematic, but the velocities are written back as
+// plain data since there is no avian body to move
+pub(crate) fn apply_internal(
+    time: Res<Time>,
+    time_physics: Option<Res<Time<Physics>>>,
     baseball_plugin_config: Res<BaseballPluginConfig>,
-    mut query_baseball: Query<(
-        &mut BaseballFlightState,
-        &Transform,
-        &mut LinearVelocity,
-        &mut GravityScale,
-    )>,
+    mut query_baseball: Query<InternalBall>,
 ) {
-    // 0.0167
-    let delta_t = time_fixed.delta_seconds_f64() * time_physics.relative_speed_f64();
-    for (mut state, transform, mut l_velo, mut gravity_scale) in &mut query_baseball {
-        if state.active {
-            let new_velo = state.update_state_and_get_velo(
-                &baseball_plugin_config,
-                transform
-                    .translation
-                    .from_bevy_to_baseball_coord()
-                    .as_dvec3(),
-                delta_t,
-            );
-            l_velo.0 = new_velo.from_baseball_coord_to_bevy().as_vec3();
-        } else {
-            gravity_scale.0 = 1.;
+    let delta_t = time.delta_seconds_f64() * relative_speed(&time_physics);
+    let config = &*baseball_plugin_config;
+    if query_baseball.iter().len() > config.parallel_threshold {
+        query_baseball
+            .par_iter_mut()
+            .for_each(|ball| internal_step(config, delta_t, ball));
+    } else {
+        query_baseball
+            .iter_mut()
+            .for_each(|ball| internal_step(config, delta_t, ball));
+    }
+}
+
+fn internal_step(
+    config: &BaseballPluginConfig,
+    delta_t: f64,
+    (mut state, mut transform, l_velo, a_velo, model): QueryItem<InternalBall>,
+) {
+    if state.active {
+        let model = model.unwrap_or(&config.model).get();
+        state.update_state(config, model, delta_t);
+        // left for `check_flight_finite` to deactivate
+        if !state.is_finite() {
+            return;
+        }
+        transform.translation = state.translation.as_vec3().from_baseball_coord_to_bevy();
+        if let Some(mut a_velo) = a_velo {
+            a_velo.0 = swap_coordinates_dvec3(&state.spin).as_vec3();
+        }
+        if let Some(mut l_velo) = l_velo {
+            l_velo.0 = state.v.from_baseball_coord_to_bevy().as_vec3();
+        }
+    }
+}
+
+type VelocityDrivenBall = (
+    &'static mut BaseballFlightState,
+    &'static Position,
+    &'static mut LinearVelocity,
+    &'static mut AngularVelocity,
+    Option<&'static AerodynamicModelHandle>,
+);
+
+// FlightIntegrationMode::VelocityDriven - overwrite the linear velocity.
+// runs in avian's `PhysicsSchedule`, once per physics step and with its delta
+pub(crate) fn apply_velocity_driven(
+    time: Res<Time>,
+    baseball_plugin_config: Res<BaseballPluginConfig>,
+    mut query_baseball: Query<VelocityDrivenBall>,
+) {
+    // already scaled by the physics speed
+    let delta_t = time.delta_seconds_f64();
+    let config = &*baseball_plugin_config;
+    if query_baseball.iter().len() > config.parallel_threshold {
+        query_baseball
+            .par_iter_mut()
+            .for_each(|ball| velocity_driven_step(config, delta_t, ball));
+    } else {
+        query_baseball
+            .iter_mut()
+            .for_each(|ball| velocity_driven_step(config, delta_t, ball));
+    }
+}
+
+fn velocity_driven_step(
+    config: &BaseballPluginConfig,
+    delta_t: f64,
+    (mut state, position, mut l_velo, mut a_velo, model): QueryItem<VelocityDrivenBall>,
+) {
+    if state.active {
+        let new_velo = state.update_state_and_get_velo(
+            config,
+            model.unwrap_or(&config.model).get(),
+            position.0.from_bevy_to_baseball_coord().as_dvec3(),
+            delta_t,
+        );
+        if !state.is_finite() || !new_velo.is_finite() {
+            return;
         }
+        l_velo.0 = new_velo.from_baseball_coord_to_bevy().as_vec3();
+        a_velo.0 = swap_coordinates_dvec3(&state.spin).as_vec3();
     }
 }
 
+type ForceDrivenBall = (
+    &'static mut BaseballFlightState,
+    &'static Position,
+    &'static LinearVelocity,
+    &'static mut ExternalForce,
+    &'static mut AngularVelocity,
+    Option<&'static AerodynamicModelHandle>,
+);
+
 // preferred
-// option 3 - apply external force
-pub(crate) fn _apply_physics_option_3(
-    time_fixed: Res<Time<Fixed>>,
-    time_physics: Res<Time<Physics>>,
+// FlightIntegrationMode::ForceDriven - apply external force.
+// runs in avian's `PhysicsSchedule`, so the flight advances exactly as far as the body does,
+// whatever avian's timestep is
+pub(crate) fn apply_force_driven(
+    time: Res<Time>,
     baseball_plugin_config: Res<BaseballPluginConfig>,
+    mut query_baseball: Query<ForceDrivenBall>,
+) {
+    // already scaled by the physics speed
+    let delta_t = time.delta_seconds_f64();
+    let config = &*baseball_plugin_config;
+    if query_baseball.iter().len() > config.parallel_threshold {
+        query_baseball
+            .par_iter_mut()
+            .for_each(|ball| force_driven_step(config, delta_t, ball));
+    } else {
+        query_baseball
+            .iter_mut()
+            .for_each(|ball| force_driven_step(config, delta_t, ball));
+    }
+}
+
+fn force_driven_step(
+    config: &BaseballPluginConfig,
+    delta_t: f64,
+    (mut state, position, l_velo, mut force, mut a_velo, model): QueryItem<ForceDrivenBall>,
+) {
+    if state.active {
+        let a = state.update_state_and_get_acceleration(
+            config,
+            model.unwrap_or(&config.model).get(),
+            position.0.from_bevy_to_baseball_coord().as_dvec3(),
+            l_velo.0.from_bevy_to_baseball_coord().as_dvec3(),
+            delta_t,
+        );
+        force.set_force(a.from_baseball_coord_to_bevy().as_vec3() * MASS);
+        force.persistent = true;
+        if state.is_finite() {
+            a_velo.0 = swap_coordinates_dvec3(&state.spin).as_vec3();
+        }
+    }
+}
+
+type ForceDrivenSubstepBall = (
+    &'static mut BaseballFlightState,
+    &'static Position,
+    &'static AccumulatedTranslation,
+    &'static LinearVelocity,
+    &'static mut ExternalForce,
+    &'static mut AngularVelocity,
+    Option<&'static AerodynamicModelHandle>,
+);
+
+// FlightIntegrationMode::ForceDriven evaluated inside avian's substeps, so the force always
+// comes from the live substep velocity instead of the velocity at the start of the physics step
+pub(crate) fn apply_force_driven_substep(
+    time_substeps: Res<Time<Substeps>>,
+    baseball_plugin_config: Res<BaseballPluginConfig>,
+    mut query_baseball: Query<ForceDrivenSubstepBall>,
+) {
+    let delta_t = time_substeps.delta_seconds_f64();
+    let config = &*baseball_plugin_config;
+    if query_baseball.iter().len() > config.parallel_threshold {
+        query_baseball
+            .par_iter_mut()
+            .for_each(|ball| force_driven_substep(config, delta_t, ball));
+    } else {
+        query_baseball
+            .iter_mut()
+            .for_each(|ball| force_driven_substep(config, delta_t, ball));
+    }
+}
+
+fn force_driven_substep(
+    config: &BaseballPluginConfig,
+    delta_t: f64,
+    (mut state, position, accumulated_translation, l_velo, mut force, mut a_velo, model): QueryItem<
+        ForceDrivenSubstepBall,
+    >,
+) {
+    if state.active {
+        let a = state.update_state_and_get_acceleration(
+            config,
+            model.unwrap_or(&config.model).get(),
+            (position.0 + accumulated_translation.0)
+                .from_bevy_to_baseball_coord()
+                .as_dvec3(),
+            l_velo.0.from_bevy_to_baseball_coord().as_dvec3(),
+            delta_t,
+        );
+        force.set_force(a.from_baseball_coord_to_bevy().as_vec3() * MASS);
+        force.persistent = true;
+        if state.is_finite() {
+            a_velo.0 = swap_coordinates_dvec3(&state.spin).as_vec3();
+        }
+    }
+}
+
+// picks the level of detail for the coming step, see `FlightLodPolicy::assign`
+pub(crate) fn apply_flight_lod(
+    policy: Res<FlightLodPolicy>,
+    query_focus: Query<&GlobalTransform, With<FlightLodFocus>>,
     mut query_baseball: Query<(
+        Entity,
         &mut BaseballFlightState,
         &Transform,
-        &LinearVelocity,
-        &mut ExternalForce,
+        Option<&FlightLod>,
     )>,
+    // kept between frames so the buffers aren't allocated every frame
+    mut focus: Local<Vec<Vec3>>,
+    mut balls: Local<Vec<(Entity, f32, Option<FlightLod>)>>,
 ) {
-    let delta_t = time_fixed.delta_seconds_f64() * time_physics.relative_speed_f64();
-    for (mut state, transform, l_velo, mut force) in &mut query_baseball {
-        if state.active {
-            let a = state.update_state_and_get_acceleration(
-                &baseball_plugin_config,
-                transform
-                    .translation
-                    .from_bevy_to_baseball_coord()
-                    .as_dvec3(),
-                l_velo.0.from_bevy_to_baseball_coord().as_dvec3(),
-                delta_t,
-            );
-            force.set_force(a.from_baseball_coord_to_bevy().as_vec3() * MASS);
-            force.persistent = true;
-        } else {
-            // info!("inactive aerodynamics");
+    focus.clear();
+    focus.extend(query_focus.iter().map(|transform| transform.translation()));
+
+    balls.clear();
+    balls.extend(
+        query_baseball
+            .iter()
+            .filter(|(_, state, _, _)| state.active)
+            .map(|(entity, _, transform, pinned)| {
+                let distance = focus
+                    .iter()
+                    .map(|focus| focus.distance(transform.translation))
+                    .reduce(f32::min)
+                    .unwrap_or(0.);
+                (entity, distance, pinned.copied())
+            }),
+    );
+    policy.assign(&mut balls);
+
+    for &(entity, _, lod) in balls.iter() {
+        let (Ok((_, mut state, _, _)), Some(lod)) = (query_baseball.get_mut(entity), lod) else {
+            continue;
+        };
+        if state.lod != lod {
+            state.lod = lod;
         }
     }
 }
 
+pub(crate) fn sync_seam_visuals(
+    query_baseball: Query<(&BaseballFlightState, &Transform), Without<BaseballVisual>>,
+    mut query_visual: Query<(&Parent, &mut Transform), With<BaseballVisual>>,
+) {
+    for (parent, mut transform) in &mut query_visual {
+        if let Ok((state, parent_transform)) = query_baseball.get(parent.get()) {
+            if state.active {
+                // avian still rotates the parent, the child undoes that
+                transform.rotation = parent_transform.rotation.inverse()
+                    * rotation_from_baseball_coord_to_bevy(state.seam_orientation);
+            }
+        }
+    }
+}
+
+type ActivatedBall = (
+    &'static mut BaseballFlightState,
+    Option<&'static mut ExternalForce>,
+    Option<&'static mut GravityScale>,
+    &'static Transform,
+    Option<&'static mut LinearVelocity>,
+    Option<&'static AngularVelocity>,
+    Option<&'static KnuckleballSeed>,
+    Option<&'static SeamGeometry>,
+);
+
 pub(crate) fn activate_aerodynamics(
-    mut ball_physics_query: Query<(
-        &mut BaseballFlightState,
-        &mut ExternalForce,
-        &mut GravityScale,
-        &Transform,
-        &LinearVelocity,
-        &AngularVelocity,
-    )>,
+    baseball_plugin_config: Res<BaseballPluginConfig>,
+    mut ball_physics_query: Query<ActivatedBall>,
     mut ev_activate_aerodynamics_event: EventReader<ActivateAerodynamicsEvent>,
     mut ev_post_activate_aerodynamics_event: EventWriter<PostActivateAerodynamicsEvent>,
 ) {
     for ev in ev_activate_aerodynamics_event.read() {
-        if let Ok((mut state, mut force, mut gravity_scale, transform, l_velo, a_velo)) =
-            ball_physics_query.get_mut(ev.entity)
+        if let Ok((
+            mut state,
+            force,
+            gravity_scale,
+            transform,
+            l_velo,
+            a_velo,
+            seed,
+            seam_geometry,
+        )) = ball_physics_query.get_mut(ev.entity)
         {
             if !state.active {
                 info!("hello???");
                 // just in case
-                force.set_force(Vec3::ZERO);
-                gravity_scale.0 = 0.;
+                if let Some(mut force) = force {
+                    force.set_force(Vec3::ZERO);
+                }
+                if let Some(mut gravity_scale) = gravity_scale {
+                    gravity_scale.0 = 0.;
+                }
                 //
+                // spin is a rate, only the axes are swapped. a ball without avian velocities
+                // is thrown with the ones already set on its state
+                let spin = a_velo.map_or(state.spin, |a_velo| {
+                    swap_coordinates_vec3(&a_velo.0).as_dvec3()
+                });
+                let velocity = l_velo.as_ref().map_or(state.v, |l_velo| {
+                    l_velo.0.from_bevy_to_baseball_coord().as_dvec3()
+                });
                 *state = BaseballFlightState::from_params(
                     transform
                         .translation
                         .from_bevy_to_baseball_coord()
                         .as_dvec3(),
-                    l_velo.0.from_bevy_to_baseball_coord().as_dvec3(),
-                    a_velo.0.from_bevy_to_baseball_coord().as_dvec3(),
-                    ev.seam_y_angle,
-                    ev.seam_z_angle,
+                    velocity,
+                    spin,
+                    ev.seam_orientation.to_quat(spin, transform.rotation),
+                    seam_geometry.unwrap_or(&baseball_plugin_config.seam_geometry),
+                );
+                // unseeded balls each get their own flutter, still reproducible from the config seed
+                let seed = seed.map_or(
+                    baseball_plugin_config.knuckleball.seed ^ ev.entity.to_bits(),
+                    |seed| seed.0,
                 );
+                state.knuckleball_flutter =
+                    KnuckleballFlutter::from_seed(&baseball_plugin_config.knuckleball, seed);
+                // the transform is teleported from here on, avian must not move the body as well
+                if baseball_plugin_config.integration_mode == FlightIntegrationMode::Kinematic {
+                    if let Some(mut l_velo) = l_velo {
+                        l_velo.0 = Vec3::ZERO;
+                    }
+                }
                 //
                 ev_post_activate_aerodynamics_event.send(PostActivateAerodynamicsEvent(ev.entity));
             }
@@ -118,20 +395,87 @@ pub(crate) fn activate_aerodynamics(
     }
 }
 
+type DisabledBall = (
+    &'static mut BaseballFlightState,
+    Option<&'static mut ExternalForce>,
+    Option<&'static mut GravityScale>,
+    Option<&'static mut LinearVelocity>,
+);
+
 pub(crate) fn disable_aerodynamics(
-    mut ball_physics_query: Query<(
-        &mut BaseballFlightState,
-        &mut ExternalForce,
-        &mut GravityScale,
-    )>,
+    baseball_plugin_config: Res<BaseballPluginConfig>,
+    mut ball_physics_query: Query<DisabledBall>,
     mut ev_disable_aerodynamics_event: EventReader<DisableAerodynamicsEvent>,
 ) {
     for ev in ev_disable_aerodynamics_event.read() {
-        if let Ok((mut ball, mut force, mut gravity_scale)) = ball_physics_query.get_mut(ev.0) {
+        if let Ok((mut ball, force, gravity_scale, l_velo)) = ball_physics_query.get_mut(ev.0) {
             if ball.active {
+                // hand the ball back to avian with the velocity it was flying at
+                if baseball_plugin_config.integration_mode == FlightIntegrationMode::Kinematic
+                    && ball.v.is_finite()
+                {
+                    if let Some(mut l_velo) = l_velo {
+                        l_velo.0 = ball.v.from_baseball_coord_to_bevy().as_vec3();
+                    }
+                }
                 ball.deactivate();
+                if let Some(mut force) = force {
+                    force.set_force(Vec3::ZERO);
+                }
+                if let Some(mut gravity_scale) = gravity_scale {
+                    gravity_scale.0 = 1.;
+                }
+            }
+        }
+    }
+}
+
+// a NaN or infinity would poison avian, so the ball is handed back to it as it was last written
+pub(crate) fn check_flight_finite(
+    query_baseball: Query<(Entity, &BaseballFlightState)>,
+    mut ev_disable_aerodynamics_event: EventWriter<DisableAerodynamicsEvent>,
+    mut ev_flight_error_event: EventWriter<FlightErrorEvent>,
+) {
+    for (entity, state) in &query_baseball {
+        if state.active && !state.is_finite() {
+            ev_disable_aerodynamics_event.send(DisableAerodynamicsEvent(entity));
+            ev_flight_error_event.send(FlightErrorEvent {
+                entity,
+                error: FlightError::NonFinite,
+            });
+        }
+    }
+}
+
+type RestoredBall = (
+    &'static mut BaseballFlightState,
+    Option<&'static mut ExternalForce>,
+    Option<&'static mut GravityScale>,
+    &'static mut Transform,
+    Option<&'static mut LinearVelocity>,
+    Option<&'static mut AngularVelocity>,
+);
+
+pub(crate) fn restore_baseball_flight(
+    mut ball_physics_query: Query<RestoredBall>,
+    mut ev_restore_baseball_flight: EventReader<RestoreBaseballFlightEvent>,
+) {
+    for ev in ev_restore_baseball_flight.read() {
+        if let Ok((mut state, force, gravity_scale, mut transform, mut l_velo, mut a_velo)) =
+            ball_physics_query.get_mut(ev.entity)
+        {
+            ev.snapshot.restore(
+                &mut state,
+                &mut transform,
+                l_velo.as_deref_mut(),
+                a_velo.as_deref_mut(),
+            );
+            // force is recomputed from the restored state on the next step
+            if let Some(mut force) = force {
                 force.set_force(Vec3::ZERO);
-                gravity_scale.0 = 1.;
+            }
+            if let Some(mut gravity_scale) = gravity_scale {
+                gravity_scale.0 = if state.active { 0. } else { 1. };
             }
         }
     }
@@ -0,0 +1,112 @@
+// shared by the app level tests, each test binary uses a different part of it
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_avian_baseball_flight::prelude::*;
+
+// the fixed timestep's default rate, so every update runs exactly one fixed step
+pub const FIXED_HZ: f64 = 64.;
+
+// avian's scene support, on with `render`, needs the scene spawner
+fn add_minimal_plugins(app: &mut App) {
+    app.add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin));
+    #[cfg(feature = "render")]
+    app.add_plugins((AssetPlugin::default(), bevy::scene::ScenePlugin));
+}
+
+/// headless app advancing `1 / hz` per update. the first update only starts the clock
+pub fn app(plugin: BaseballFlightPlugin, hz: f64, physics: bool) -> App {
+    let mut app = App::new();
+    add_minimal_plugins(&mut app);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_nanos(
+        (1e9 / hz).round() as u64,
+    )));
+    if physics {
+        app.add_plugins(PhysicsPlugins::default());
+    }
+    app.add_plugins(plugin);
+    app.update();
+    app
+}
+
+/// headless app updating at 100 Hz around a fixed timestep of `1 / fixed_hz`, with avian in
+/// `FixedPostUpdate` stepping once per fixed step. the first update only starts the clock
+pub fn fixed_app(plugin: BaseballFlightPlugin, fixed_hz: f64) -> App {
+    let mut app = App::new();
+    add_minimal_plugins(&mut app);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        10,
+    )));
+    app.insert_resource(Time::<Fixed>::from_hz(fixed_hz));
+    app.add_plugins(PhysicsPlugins::new(FixedPostUpdate));
+    app.insert_resource(Time::new_with(Physics::fixed_once_hz(fixed_hz)));
+    app.add_plugins(plugin);
+    app.update();
+    app
+}
+
+pub fn plugin(integration_mode: FlightIntegrationMode) -> BaseballFlightPlugin {
+    BaseballFlightPlugin {
+        integration_mode,
+        ..default()
+    }
+}
+
+/// ~90 mph with ~2200 rpm of backspin, released 18 m from the plate towards -z
+pub fn spawn_pitch(app: &mut App, bundle: impl Bundle) -> Entity {
+    let spin = Vec3::new(-2200., 0., 0.) * RPM_TO_RADS;
+    app.world_mut()
+        .spawn((
+            bundle,
+            TransformBundle::from_transform(Transform::from_xyz(0., 1.8, 18.)),
+            LinearVelocity(Vec3::new(0., 0., -40.)),
+            AngularVelocity(swap_coordinates_vec3(&spin)),
+        ))
+        .id()
+}
+
+/// activates the ball, the update it takes also flies the first step
+pub fn throw(app: &mut App, entity: Entity) {
+    app.world_mut()
+        .send_event(ActivateAerodynamicsEvent::from_angles(entity, 0., 0.));
+    app.update();
+}
+
+pub fn run(app: &mut App, updates: usize) {
+    for _ in 0..updates {
+        app.update();
+    }
+}
+
+pub fn state(app: &App, entity: Entity) -> &BaseballFlightState {
+    app.world()
+        .get::<BaseballFlightState>(entity)
+        .expect("ball has a flight state")
+}
+
+pub fn translation(app: &App, entity: Entity) -> Vec3 {
+    app.world()
+        .get::<Transform>(entity)
+        .expect("ball has a transform")
+        .translation
+}
+
+/// every event of this kind sent over the last two updates
+pub fn events<E: Event + Clone>(app: &App) -> Vec<E> {
+    let events = app.world().resource::<Events<E>>();
+    events.get_reader().read(events).cloned().collect()
+}
+
+pub fn assert_same_flight(a: &BaseballFlightState, b: &BaseballFlightState) {
+    assert_eq!(a.translation, b.translation);
+    assert_eq!(a.v, b.v);
+    assert_eq!(a.spin, b.spin);
+    assert_eq!(a.seam_orientation, b.seam_orientation);
+    assert_eq!(a.force_breakdown, b.force_breakdown);
+    assert_eq!(a.time_elapsed, b.time_elapsed);
+    assert_eq!(a.step_remainder, b.step_remainder);
+    assert_eq!(a.active, b.active);
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_avian_baseball_flight::prelude::*;
+use common::*;
+
+#[test]
+fn non_finite_flight_is_stopped_and_reported() {
+    let mut app = app(plugin(FlightIntegrationMode::Internal), FIXED_HZ, false);
+    let ball = spawn_pitch(&mut app, BaseballFlightState::default());
+    app.world_mut().get_mut::<LinearVelocity>(ball).unwrap().0 = Vec3::new(0., 0., f32::NAN);
+    throw(&mut app, ball);
+
+    assert_eq!(
+        events::<FlightErrorEvent>(&app),
+        vec![FlightErrorEvent {
+            entity: ball,
+            error: FlightError::NonFinite,
+        }]
+    );
+    assert!(!state(&app, ball).active);
+    assert!(translation(&app, ball).is_finite());
+
+    // nothing is flown or reported again
+    run(&mut app, 3);
+    assert!(events::<FlightErrorEvent>(&app).is_empty());
+    assert!(translation(&app, ball).is_finite());
+}
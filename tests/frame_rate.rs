@@ -0,0 +1,141 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::{math::DVec3, prelude::*};
+use bevy_avian_baseball_flight::prelude::*;
+use common::*;
+
+// how long every flight is compared for, in s
+const FLIGHT: f64 = 0.5;
+
+// 0.5 s is a whole number of updates or fixed steps at all of these
+const RATES: [f64; 3] = [50., 60., 64.];
+
+// (flight time, position) after every update, from `sample`, until the ball has flown `FLIGHT`
+fn fly<T>(
+    mut app: App,
+    bundle: impl Bundle,
+    sample: impl Fn(&App, Entity) -> (f64, T),
+) -> Vec<(f64, T)> {
+    let ball = spawn_pitch(&mut app, bundle);
+    throw(&mut app, ball);
+
+    let mut samples = Vec::new();
+    loop {
+        samples.push(sample(&app, ball));
+        let state = state(&app, ball);
+        if state.time_elapsed + state.step_remainder >= FLIGHT - 1e-6 {
+            return samples;
+        }
+        app.update();
+    }
+}
+
+// the flight state, as far as it has been stepped
+fn flight_state(app: &App, ball: Entity) -> (f64, DVec3) {
+    let state = state(app, ball);
+    (state.time_elapsed, state.translation)
+}
+
+// the avian body, which has also flown the time the state still has to step
+fn body(app: &App, ball: Entity) -> (f64, Vec3) {
+    let state = state(app, ball);
+    (
+        state.time_elapsed + state.step_remainder,
+        translation(app, ball),
+    )
+}
+
+// every pair of runs agrees within `tolerance` wherever both have a sample at the same flight
+// time, to within the timesteps' rounding to ns, and some of them line up in the last 0.1 s
+fn assert_same_trajectory<T: Copy + std::fmt::Debug>(
+    runs: &[Vec<(f64, T)>],
+    distance: impl Fn(T, T) -> f64,
+    tolerance: f64,
+) {
+    for (i, run) in runs.iter().enumerate() {
+        for other in &runs[i + 1..] {
+            let mut compared = 0.;
+            for &(time, position) in run {
+                if let Some(&(_, other_position)) = other
+                    .iter()
+                    .find(|(other_time, _)| (other_time - time).abs() < 1e-6)
+                {
+                    let off = distance(position, other_position);
+                    assert!(
+                        off < tolerance,
+                        "positions differ by {off} after {time} s: {position:?} vs {other_position:?}"
+                    );
+                    compared = time;
+                }
+            }
+            assert!(
+                compared >= FLIGHT - 0.1,
+                "runs only lined up until {compared} s"
+            );
+        }
+    }
+}
+
+fn internal(schedule: impl bevy::ecs::schedule::ScheduleLabel) -> BaseballFlightPlugin {
+    BaseballFlightPlugin {
+        integration_mode: FlightIntegrationMode::Internal,
+        ..BaseballFlightPlugin::new(schedule)
+    }
+}
+
+#[test]
+fn trajectory_does_not_depend_on_the_update_rate() {
+    let runs = RATES.map(|hz| {
+        fly(
+            app(internal(Update), hz, false),
+            BaseballFlightState::default(),
+            flight_state,
+        )
+    });
+    assert_same_trajectory(&runs, DVec3::distance, 1e-9);
+}
+
+#[test]
+fn trajectory_does_not_depend_on_the_fixed_timestep() {
+    let runs = RATES.map(|hz| {
+        fly(
+            fixed_app(internal(FixedUpdate), hz),
+            BaseballFlightState::default(),
+            flight_state,
+        )
+    });
+    assert_same_trajectory(&runs, DVec3::distance, 1e-9);
+}
+
+#[test]
+fn force_driven_trajectory_barely_depends_on_the_fixed_timestep() {
+    let runs = RATES.map(|hz| {
+        fly(
+            fixed_app(BaseballFlightPlugin::default(), hz),
+            (BaseballFlightBundle::default(), ExternalForce::default()),
+            body,
+        )
+    });
+    // avian's semi implicit euler steps differ with the rate, by a fraction of an inch
+    assert_same_trajectory(&runs, |a: Vec3, b| a.distance(b) as f64, 0.0254);
+}
+
+#[test]
+fn dormand_prince_trajectory_does_not_depend_on_the_update_rate() {
+    let runs = RATES.map(|hz| {
+        fly(
+            app(
+                BaseballFlightPlugin {
+                    integrator: Integrator::dormand_prince(),
+                    ..internal(Update)
+                },
+                hz,
+                false,
+            ),
+            BaseballFlightState::default(),
+            flight_state,
+        )
+    });
+    assert_same_trajectory(&runs, DVec3::distance, 1e-9);
+}
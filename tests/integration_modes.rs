@@ -0,0 +1,171 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_avian_baseball_flight::prelude::*;
+use common::*;
+
+// the internal ball follows the 64 Hz fixed timestep, the force driven one avian's own 60 Hz
+// clock, so their flights line up every 0.25 s
+const UPDATES: usize = 40;
+
+// flight time in whole ms -> position, after every update. the force driven body has flown the
+// state's leftover time as well, so it's only sampled when there's none
+fn fly(plugin: BaseballFlightPlugin, bundle: impl Bundle) -> Vec<(u64, Vec3)> {
+    let mut app = app(plugin, FIXED_HZ, true);
+    let ball = spawn_pitch(&mut app, bundle);
+    throw(&mut app, ball);
+
+    let mut samples = Vec::new();
+    for _ in 0..UPDATES {
+        let state = state(&app, ball);
+        let flown = (state.time_elapsed + state.step_remainder) * 1000.;
+        if (flown - flown.round()).abs() < 1e-3 {
+            samples.push((flown.round() as u64, translation(&app, ball)));
+        }
+        app.update();
+    }
+    samples
+}
+
+// compares a force driven flight with the internal one wherever their flight times line up
+fn assert_follows_the_internal_trajectory(force_driven: BaseballFlightPlugin) {
+    let internal = fly(
+        plugin(FlightIntegrationMode::Internal),
+        BaseballFlightState::default(),
+    );
+    let force_driven = fly(
+        force_driven,
+        (BaseballFlightBundle::default(), ExternalForce::default()),
+    );
+
+    let mut compared = 0;
+    for (ms, translation) in &force_driven {
+        if let Some((_, internal_translation)) = internal.iter().find(|(other, _)| other == ms) {
+            // avian integrates the force with semi implicit euler substeps, which drifts from
+            // rk4 by a fraction of an inch over the flight
+            let distance = translation.distance(*internal_translation);
+            assert!(
+                distance < 0.0254,
+                "force driven ball is {distance} m off the internal trajectory after {ms} ms"
+            );
+            compared = compared.max(*ms);
+        }
+    }
+    assert!(compared >= 500, "flights only lined up until {compared} ms");
+}
+
+#[test]
+fn force_driven_follows_the_internal_trajectory() {
+    assert_follows_the_internal_trajectory(plugin(FlightIntegrationMode::ForceDriven));
+}
+
+#[test]
+fn substep_aerodynamics_follow_the_internal_trajectory() {
+    assert_follows_the_internal_trajectory(BaseballFlightPlugin {
+        substep_aerodynamics: true,
+        ..plugin(FlightIntegrationMode::ForceDriven)
+    });
+}
+
+// throws the ball, checks it flies towards the plate and disables it again
+fn fly_and_disable(mode: FlightIntegrationMode, bundle: impl Bundle) -> (App, Entity) {
+    let mut app = app(plugin(mode), FIXED_HZ, true);
+    let ball = spawn_pitch(&mut app, bundle);
+    let release = translation(&app, ball);
+    throw(&mut app, ball);
+    run(&mut app, 10);
+
+    assert!(state(&app, ball).active, "{mode:?} ball is not flying");
+    // 11 fixed steps at 40 m/s is ~7 m
+    let flown = release.z - translation(&app, ball).z;
+    assert!(flown > 5., "{mode:?} ball flew {flown} m towards the plate");
+
+    app.world_mut().send_event(DisableAerodynamicsEvent(ball));
+    app.update();
+    assert!(!state(&app, ball).active, "{mode:?} ball is still flying");
+    (app, ball)
+}
+
+#[test]
+fn kinematic_ball_is_handed_back_its_velocity() {
+    let (app, ball) = fly_and_disable(
+        FlightIntegrationMode::Kinematic,
+        BaseballFlightBundle::kinematic(),
+    );
+    let velocity = app.world().get::<LinearVelocity>(ball).unwrap().0;
+    assert!(
+        velocity.z < -30.,
+        "kinematic ball was handed back {velocity}"
+    );
+}
+
+#[test]
+fn velocity_driven_ball_falls_under_avian_gravity_again() {
+    let (app, ball) = fly_and_disable(
+        FlightIntegrationMode::VelocityDriven,
+        BaseballFlightBundle::default(),
+    );
+    assert_eq!(app.world().get::<GravityScale>(ball).unwrap().0, 1.);
+    let velocity = app.world().get::<LinearVelocity>(ball).unwrap().0;
+    assert!(
+        velocity.z < -30.,
+        "velocity driven ball slowed to {velocity}"
+    );
+}
+
+#[test]
+fn force_driven_ball_loses_its_force() {
+    let (app, ball) = fly_and_disable(
+        FlightIntegrationMode::ForceDriven,
+        (BaseballFlightBundle::default(), ExternalForce::default()),
+    );
+    assert_eq!(app.world().get::<GravityScale>(ball).unwrap().0, 1.);
+    assert_eq!(
+        app.world().get::<ExternalForce>(ball).unwrap().force(),
+        Vec3::ZERO
+    );
+}
+
+#[test]
+fn internal_ball_stops_moving() {
+    let (mut app, ball) = fly_and_disable(
+        FlightIntegrationMode::Internal,
+        BaseballFlightState::default(),
+    );
+    let stopped_at = translation(&app, ball);
+    run(&mut app, 5);
+    assert_eq!(translation(&app, ball), stopped_at);
+}
+
+#[test]
+fn internal_ball_flies_without_avian_components() {
+    let mut app = app(plugin(FlightIntegrationMode::Internal), FIXED_HZ, false);
+    let with_velocities = spawn_pitch(&mut app, BaseballFlightState::default());
+    let world = app.world();
+    let v = world.get::<LinearVelocity>(with_velocities).unwrap().0;
+    let spin = world.get::<AngularVelocity>(with_velocities).unwrap().0;
+    let bare = app
+        .world_mut()
+        .spawn((
+            // baseball coord
+            BaseballFlightState {
+                v: v.from_bevy_to_baseball_coord().as_dvec3(),
+                spin: swap_coordinates_vec3(&spin).as_dvec3(),
+                ..default()
+            },
+            TransformBundle::from_transform(Transform::from_xyz(0., 1.8, 18.)),
+        ))
+        .id();
+
+    app.world_mut().send_event_batch([
+        ActivateAerodynamicsEvent::from_angles(with_velocities, 0., 0.),
+        ActivateAerodynamicsEvent::from_angles(bare, 0., 0.),
+    ]);
+    run(&mut app, 10);
+
+    assert!(state(&app, bare).active);
+    assert!(translation(&app, bare).z < 18. - 5.);
+    assert_same_flight(state(&app, with_velocities), state(&app, bare));
+    assert_eq!(translation(&app, with_velocities), translation(&app, bare));
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_avian_baseball_flight::prelude::*;
+use common::*;
+
+fn knuckleball_plugin() -> BaseballFlightPlugin {
+    let mut plugin = plugin(FlightIntegrationMode::Internal);
+    plugin.ssw_on = false;
+    plugin
+}
+
+// flies two ~50 rpm knuckleballs side by side and returns where they are
+fn fly_pair(a: impl Bundle, b: impl Bundle) -> (Vec3, Vec3) {
+    let mut app = app(knuckleball_plugin(), FIXED_HZ, false);
+    app.world_mut()
+        .resource_mut::<BaseballPluginConfig>()
+        .knuckleball
+        .enabled = true;
+    let balls = [
+        spawn_pitch(&mut app, (BaseballFlightState::default(), a)),
+        spawn_pitch(&mut app, (BaseballFlightState::default(), b)),
+    ];
+    for ball in balls {
+        app.world_mut()
+            .get_mut::<AngularVelocity>(ball)
+            .unwrap()
+            .0 = Vec3::new(-50., 0., 0.) * RPM_TO_RADS;
+        app.world_mut()
+            .send_event(ActivateAerodynamicsEvent::from_angles(ball, 0., 0.));
+    }
+    run(&mut app, 30);
+    (translation(&app, balls[0]), translation(&app, balls[1]))
+}
+
+#[test]
+fn equal_seeds_flutter_the_same() {
+    let (a, b) = fly_pair(KnuckleballSeed(7), KnuckleballSeed(7));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn different_seeds_flutter_differently() {
+    let (a, b) = fly_pair(KnuckleballSeed(7), KnuckleballSeed(8));
+    assert!(a.distance(b) > 0.001, "{a} vs {b}");
+}
+
+#[test]
+fn unseeded_balls_flutter_differently() {
+    let (a, b) = fly_pair((), ());
+    assert!(a.distance(b) > 0.001, "{a} vs {b}");
+}
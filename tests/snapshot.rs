@@ -0,0 +1,70 @@
+mod common;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_avian_baseball_flight::prelude::*;
+use common::*;
+
+fn capture(app: &App, entity: Entity) -> BaseballFlightSnapshot {
+    let world = app.world();
+    BaseballFlightSnapshot::capture(
+        world.get::<BaseballFlightState>(entity).unwrap(),
+        world.get::<Transform>(entity).unwrap(),
+        world.get::<LinearVelocity>(entity),
+        world.get::<AngularVelocity>(entity),
+    )
+}
+
+// an empty ball for a snapshot to be restored into
+fn spawn_empty(app: &mut App) -> Entity {
+    app.world_mut()
+        .spawn((
+            BaseballFlightState::default(),
+            TransformBundle::default(),
+            LinearVelocity::default(),
+            AngularVelocity::default(),
+        ))
+        .id()
+}
+
+fn restore(app: &mut App, entity: Entity, snapshot: BaseballFlightSnapshot) {
+    app.world_mut()
+        .send_event(RestoreBaseballFlightEvent { entity, snapshot });
+}
+
+#[test]
+fn restored_flight_continues_identically() {
+    let mut app = app(plugin(FlightIntegrationMode::Internal), FIXED_HZ, false);
+    let original = spawn_pitch(&mut app, BaseballFlightState::default());
+    throw(&mut app, original);
+    run(&mut app, 10);
+
+    let snapshot = capture(&app, original);
+    let restored = spawn_empty(&mut app);
+    restore(&mut app, restored, snapshot);
+    // restores run before the step, so both balls fly this update from the same state
+    run(&mut app, 10);
+
+    assert!(state(&app, original).active);
+    assert_same_flight(state(&app, original), state(&app, restored));
+    assert_eq!(translation(&app, original), translation(&app, restored));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialized_flight_continues_identically() {
+    let mut app = app(plugin(FlightIntegrationMode::Internal), FIXED_HZ, false);
+    let original = spawn_pitch(&mut app, BaseballFlightState::default());
+    throw(&mut app, original);
+    run(&mut app, 10);
+
+    let json = serde_json::to_string(&capture(&app, original)).unwrap();
+    let snapshot: BaseballFlightSnapshot = serde_json::from_str(&json).unwrap();
+    assert_same_flight(&snapshot.state, state(&app, original));
+
+    let restored = spawn_empty(&mut app);
+    restore(&mut app, restored, snapshot);
+    run(&mut app, 10);
+
+    assert_same_flight(state(&app, original), state(&app, restored));
+}